@@ -0,0 +1,180 @@
+use std::{fmt, io::Cursor};
+
+use chrono::{Duration, NaiveDate};
+use image::{ImageFormat, RgbImage};
+use plotters::{coord::Shift, prelude::*};
+
+use crate::{db::Database, github::{DailySeries, Event, GithubData}};
+
+pub static CHART_FILENAME: &str = "trends.png";
+static WIDTH: u32 = 1200;
+static HEIGHT: u32 = 1000;
+static FONT: &str = "sans-serif";
+
+#[derive(Debug)]
+pub enum ChartError {
+    Database(rusqlite::Error),
+    Drawing(String),
+    Encoding(image::ImageError),
+}
+
+impl fmt::Display for ChartError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Database(why) => write!(f, "could not read the cached issues: {why}"),
+            Self::Drawing(why) => write!(f, "could not draw the chart: {why}"),
+            Self::Encoding(why) => write!(f, "could not encode the chart: {why}"),
+        }
+    }
+}
+
+impl std::error::Error for ChartError {}
+
+impl<E: std::error::Error + Send + Sync> From<DrawingAreaErrorKind<E>> for ChartError {
+    fn from(why: DrawingAreaErrorKind<E>) -> Self {
+        Self::Drawing(why.to_string())
+    }
+}
+
+/// Daily series over a range of days, oldest first.
+#[derive(Debug, Default, PartialEq)]
+pub struct Trends {
+    pub days: Vec<NaiveDate>,
+    pub opened_prs: Vec<usize>,
+    pub merged_prs: Vec<usize>,
+    pub opened_issues: Vec<usize>,
+    pub closed_issues: Vec<usize>,
+    /// Open issues and pull requests at the end of each day.
+    pub open_issues: Vec<usize>,
+    pub open_prs: Vec<usize>,
+}
+
+impl Trends {
+    /// Trends of the `days` days up to and including `last_day`.
+    pub fn new(series: &DailySeries, last_day: NaiveDate, days: usize) -> Self {
+        let first_day = last_day - Duration::days(days as i64 - 1);
+        let days: Vec<NaiveDate> = first_day.iter_days().take(days).collect();
+        let open = |opened: Event, closed: Event| days.iter()
+            .map(|day| series.until_end_of(opened, *day) - series.until_end_of(closed, *day))
+            .collect();
+        Self {
+            opened_prs: series.daily(Event::OpenedPr, first_day, last_day),
+            merged_prs: series.daily(Event::MergedPr, first_day, last_day),
+            opened_issues: series.daily(Event::OpenedIssue, first_day, last_day),
+            closed_issues: series.daily(Event::ClosedIssue, first_day, last_day),
+            open_issues: open(Event::OpenedIssue, Event::ClosedIssue),
+            open_prs: open(Event::OpenedPr, Event::ClosedPr),
+            days,
+        }
+    }
+
+    /// Trends of the cached issues and pull requests of every repository of the report.
+    pub fn load(database: &Database, report: &GithubData, last_day: NaiveDate, days: usize) -> Result<Self, ChartError> {
+        let mut issues = Vec::new();
+        let mut pull_requests = Vec::new();
+        for repository in report.repositories() {
+            issues.extend(database.issues(repository).map_err(ChartError::Database)?);
+            pull_requests.extend(database.pull_requests(repository).map_err(ChartError::Database)?);
+        }
+        let series = DailySeries::new(&issues, &pull_requests, report.timezone(), report.date());
+        Ok(Self::new(&series, last_day, days))
+    }
+
+    /// Renders the pull request, issue and backlog charts stacked in a single PNG.
+    pub fn render(&self, title: &str) -> Result<Vec<u8>, ChartError> {
+        let mut buffer = vec![0; (WIDTH * HEIGHT * 3) as usize];
+        {
+            let root = BitMapBackend::with_buffer(&mut buffer, (WIDTH, HEIGHT)).into_drawing_area();
+            root.fill(&WHITE)?;
+            let root = root.titled(title, (FONT, 30))?;
+            let panels = root.split_evenly((3, 1));
+            self.draw(&panels[0], "Pull requests per day", &[("Opened", &self.opened_prs, BLUE), ("Merged", &self.merged_prs, MAGENTA)])?;
+            self.draw(&panels[1], "Issues per day", &[("Opened", &self.opened_issues, RED), ("Closed", &self.closed_issues, GREEN)])?;
+            self.draw(&panels[2], "Open backlog", &[("Issues", &self.open_issues, RED), ("Pull requests", &self.open_prs, BLUE)])?;
+            root.present()?;
+        }
+
+        let image = RgbImage::from_raw(WIDTH, HEIGHT, buffer).ok_or(ChartError::Drawing(String::from("buffer does not match the chart size")))?;
+        let mut png = Cursor::new(Vec::new());
+        image.write_to(&mut png, ImageFormat::Png).map_err(ChartError::Encoding)?;
+        Ok(png.into_inner())
+    }
+
+    fn draw(&self, area: &DrawingArea<BitMapBackend, Shift>, caption: &str, series: &[(&str, &[usize], RGBColor)]) -> Result<(), ChartError> {
+        let max = series.iter().flat_map(|(_, values, _)| values.iter()).copied().max().unwrap_or(0).max(1);
+        let mut chart = ChartBuilder::on(area)
+            .caption(caption, (FONT, 20))
+            .margin(10)
+            .margin_right(30)
+            .x_label_area_size(30)
+            .y_label_area_size(50)
+            .build_cartesian_2d(0..self.days.len().saturating_sub(1).max(1), 0..max + max / 10 + 1)?;
+        chart.configure_mesh()
+            .x_labels(10)
+            .x_label_formatter(&|i| self.days.get(*i).map(|d| d.format("%m-%d").to_string()).unwrap_or_default())
+            .draw()?;
+        for (name, values, colour) in series {
+            chart.draw_series(LineSeries::new(values.iter().copied().enumerate(), colour.stroke_width(2)))?
+                .label(*name)
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], colour.stroke_width(2)));
+        }
+        chart.configure_series_labels()
+            .position(SeriesLabelPosition::UpperLeft)
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .draw()?;
+        Ok(())
+    }
+}
+
+/// Draws the trends of the report over the `days` days up to its date.
+pub fn render(database: &Database, report: &GithubData, days: usize) -> Result<Vec<u8>, ChartError> {
+    let trends = Trends::load(database, report, report.date().with_timezone(&report.timezone()).date_naive(), days)?;
+    trends.render(&format!("{}, last {days} days", report.name()))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, TimeZone, Utc};
+    use chrono_tz::Tz;
+
+    use crate::{charts::*, github::{test_support, PRState, ParsedIssue, ParsedPR}};
+
+    fn date(day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap()
+    }
+
+    fn issue(created: u32, closed: Option<u32>) -> ParsedIssue {
+        test_support::issue(date(created), closed.map(date))
+    }
+
+    fn pull_request(created: u32, closed: Option<u32>, state: PRState) -> ParsedPR {
+        test_support::pull_request(date(created), closed.map(date), state)
+    }
+
+    #[test]
+    fn trends_are_bucketed_by_day() {
+        let issues = [issue(1, Some(3)), issue(3, None), issue(5, Some(6))];
+        let pull_requests = [
+            pull_request(2, Some(4), PRState::Merged),
+            pull_request(4, Some(5), PRState::Closed),
+            pull_request(5, None, PRState::Ready),
+        ];
+        let trends = Trends::new(&DailySeries::new(&issues, &pull_requests, Tz::UTC, date(5)), date(5).date_naive(), 3);
+        assert_eq!(trends.days, vec![date(3).date_naive(), date(4).date_naive(), date(5).date_naive()]);
+        assert_eq!(trends.opened_issues, vec![1, 0, 1]);
+        assert_eq!(trends.closed_issues, vec![1, 0, 0]);
+        assert_eq!(trends.opened_prs, vec![0, 1, 1]);
+        assert_eq!(trends.merged_prs, vec![0, 1, 0]);
+        assert_eq!(trends.open_issues, vec![1, 1, 2]);
+        assert_eq!(trends.open_prs, vec![1, 1, 1]);
+    }
+
+    #[test]
+    fn trends_render_as_png() {
+        let series = DailySeries::new(&[issue(1, Some(3))], &[pull_request(2, None, PRState::Ready)], Tz::UTC, date(7));
+        let trends = Trends::new(&series, date(7).date_naive(), 7);
+        let png = trends.render("octo/cat, last 7 days").unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+    }
+}
@@ -0,0 +1,168 @@
+use serenity::all::{CommandInteraction, CommandOptionType, CreateAttachment, CreateCommand, CreateCommandOption, CreateInteractionResponseMessage, ResolvedOption, ResolvedValue};
+
+use crate::{charts::{self, CHART_FILENAME}, config::Config, db::Database, github::{fit_message, GithubData, Window}};
+
+pub fn register(config: &Config) -> Vec<CreateCommand> {
+    let repository = || {
+        let mut option = CreateCommandOption::new(CommandOptionType::String, "repository", "Repository to report on, all of them by default");
+        for repository in config.github.repositories.iter().take(24) {
+            option = option.add_string_choice(repository.to_string(), repository.to_string());
+        }
+        if config.github.repositories.len() > 1 {
+            let scope = config.github.repositories.iter().map(|r| r.to_string()).collect::<Vec<String>>().join(",");
+            option = option.add_string_choice("All repositories", scope);
+        }
+        option
+    };
+    let public = || CreateCommandOption::new(CommandOptionType::Boolean, "public", "Show the answer to everyone in the channel");
+
+    let window = || {
+        let mut option = CreateCommandOption::new(CommandOptionType::String, "window", "Reporting window, the first one of the report by default");
+        for window in &config.report.windows {
+            option = option.add_string_choice(window.label(), window.to_string());
+        }
+        option
+    };
+
+    vec![
+        CreateCommand::new("ping").description("Check that the bot is alive"),
+        CreateCommand::new("stats")
+            .description("Pull request and issue statistics for a reporting window")
+            .add_option(window())
+            .add_option(repository())
+            .add_option(public()),
+        CreateCommand::new("latency")
+            .description("Time to first review and to merge pull requests, and to close issues, for a reporting window")
+            .add_option(window())
+            .add_option(repository())
+            .add_option(public()),
+        CreateCommand::new("reviews")
+            .description("Pull requests waiting on review, open review requests and reviews per reviewer for a reporting window")
+            .add_option(window())
+            .add_option(repository())
+            .add_option(public()),
+        CreateCommand::new("labels")
+            .description("Open issues, median age and issues opened and closed per label for a reporting window")
+            .add_option(window())
+            .add_option(repository())
+            .add_option(public()),
+        CreateCommand::new("stale")
+            .description("Open issues and pull requests that were not updated for the longest time")
+            .add_option(CreateCommandOption::new(CommandOptionType::String, "kind", "Only list issues or pull requests")
+                .add_string_choice("Issues", "issues")
+                .add_string_choice("Pull requests", "prs"))
+            .add_option(CreateCommandOption::new(CommandOptionType::Integer, "count", "Number of items to list")
+                .min_int_value(1)
+                .max_int_value(config.lists.stale as u64))
+            .add_option(repository())
+            .add_option(public()),
+        CreateCommand::new("uncategorised")
+            .description("Oldest open issues in none of the categories, which still need triaging")
+            .add_option(CreateCommandOption::new(CommandOptionType::Integer, "count", "Number of issues to list")
+                .min_int_value(1)
+                .max_int_value(config.lists.stale as u64))
+            .add_option(repository())
+            .add_option(public()),
+        CreateCommand::new("recent")
+            .description("Most recently created issues and pull requests")
+            .add_option(repository())
+            .add_option(public()),
+        CreateCommand::new("chart")
+            .description("Daily opened and closed issues and pull requests, and the open backlog")
+            .add_option(CreateCommandOption::new(CommandOptionType::Integer, "days", "Number of days covered by the chart")
+                .min_int_value(7)
+                .max_int_value(365))
+            .add_option(repository())
+            .add_option(public()),
+    ]
+}
+
+pub fn respond(command: &CommandInteraction, reports: &[GithubData], database: &Database, config: &Config) -> CreateInteractionResponseMessage {
+    let options = command.data.options();
+    let public = matches!(option(&options, "public"), Some(ResolvedValue::Boolean(true)));
+    let response = CreateInteractionResponseMessage::new().ephemeral(!public);
+    match (command.data.name.as_str(), find_report(reports, &options)) {
+        ("ping", _) => response.content("Pong!"),
+        (_, None) => response.content("No report is available yet, try again in a few minutes."),
+        ("chart", Some(report)) => {
+            let days = match option(&options, "days") {
+                Some(ResolvedValue::Integer(days)) => (*days).clamp(7, 365) as usize,
+                _ => config.charts.days as usize,
+            };
+            match charts::render(database, report, days) {
+                Ok(png) => response.add_file(CreateAttachment::bytes(png, CHART_FILENAME)),
+                Err(why) => {
+                    eprintln!("Error drawing the chart of {}: {why}", report.scope());
+                    response.content(format!("Could not draw the chart: {why}"))
+                },
+            }
+        },
+        (name, Some(report)) => response.content(run(name, &options, report, config)),
+    }
+}
+
+fn run(name: &str, options: &[ResolvedOption], report: &GithubData, config: &Config) -> String {
+    match name {
+        "stats" => match window(options, report) {
+            Some(window) => fit_message(&report.render_window(window)),
+            None => String::from("No reporting window is configured"),
+        },
+        "latency" => match window(options, report) {
+            Some(window) => fit_message(&report.render_window_latency(window)),
+            None => String::from("No reporting window is configured"),
+        },
+        "reviews" => match window(options, report) {
+            Some(window) => fit_message(&report.render_window_reviews(window, report.timestamps())),
+            None => String::from("No reporting window is configured"),
+        },
+        "labels" => match window(options, report) {
+            Some(window) => fit_message(&report.render_window_labels(window)),
+            None => String::from("No reporting window is configured"),
+        },
+        "stale" => {
+            let count = match option(options, "count") {
+                Some(ResolvedValue::Integer(count)) => (*count).max(1) as usize,
+                _ => config.lists.stale as usize,
+            };
+            let mut md = format!("# Stales of {}\n\n", report.title());
+            match option(options, "kind") {
+                Some(ResolvedValue::String("issues")) => md.push_str(&report.render_stale_issues(count, report.timestamps())),
+                Some(ResolvedValue::String("prs")) => md.push_str(&report.render_stale_pull_requests(count, report.timestamps())),
+                _ => {
+                    md.push_str(&report.render_stale_pull_requests(count, report.timestamps()));
+                    md.push_str(&report.render_stale_issues(count, report.timestamps()));
+                },
+            }
+            fit_message(&md)
+        },
+        "uncategorised" => {
+            let count = match option(options, "count") {
+                Some(ResolvedValue::Integer(count)) => (*count).max(1) as usize,
+                _ => config.lists.stale as usize,
+            };
+            fit_message(&format!("# Triage of {}\n\n{}", report.title(), report.render_uncategorised_issues(count, report.timestamps())))
+        },
+        "recent" => fit_message(&format!("# Last Created in {}\n\n{}{}", report.title(), report.render_recent_pull_requests(report.timestamps()), report.render_recent_issues(report.timestamps()))),
+        name => format!("Unknown command /{name}"),
+    }
+}
+
+/// The window picked in `options`, or the first window of the report.
+fn window(options: &[ResolvedOption], report: &GithubData) -> Option<Window> {
+    match option(options, "window") {
+        Some(ResolvedValue::String(window)) => window.parse().ok(),
+        _ => None,
+    }.or(report.windows().first().copied())
+}
+
+fn option<'a>(options: &'a [ResolvedOption<'a>], name: &str) -> Option<&'a ResolvedValue<'a>> {
+    options.iter().find(|o| o.name == name).map(|o| &o.value)
+}
+
+/// The report asked for, or the last one, which covers every repository.
+fn find_report<'a>(reports: &'a [GithubData], options: &[ResolvedOption]) -> Option<&'a GithubData> {
+    match option(options, "repository") {
+        Some(ResolvedValue::String(scope)) => reports.iter().find(|r| r.scope() == *scope),
+        _ => reports.last(),
+    }
+}
@@ -0,0 +1,378 @@
+use std::{collections::HashSet, fmt, fs, io, path::Path};
+
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::github::{Category, LabelPattern, Repository, Window};
+
+pub static DEFAULT_CONFIG_PATH: &str = "xatu.toml";
+/// Windows beyond this make the stats unreadable, and the embeds too long.
+static MAX_WINDOWS: usize = 8;
+/// Each category is a field of the raw stats embed, which holds at most 25 along with the pull request ones.
+static MAX_CATEGORIES: usize = 16;
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(String, io::Error),
+    Parse(toml::de::Error),
+    Invalid(Vec<String>),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(path, why) => write!(f, "could not read {path}: {why}"),
+            Self::Parse(why) => write!(f, "could not parse configuration: {why}"),
+            Self::Invalid(problems) => write!(f, "invalid configuration:\n  - {}", problems.join("\n  - ")),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub charts: ChartConfig,
+    pub database: DatabaseConfig,
+    pub discord: DiscordConfig,
+    pub github: GithubConfig,
+    pub labels: LabelConfig,
+    pub lists: ListConfig,
+    pub recording: RecordingConfig,
+    pub report: ReportConfig,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ChartConfig {
+    /// Number of days covered by the trend charts.
+    pub days: u16,
+    /// Attach the trend chart to the scheduled report.
+    pub scheduled: bool,
+}
+
+impl Default for ChartConfig {
+    fn default() -> Self {
+        Self { days: 30, scheduled: false }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseConfig {
+    /// SQLite database caching issues and pull requests between refreshes.
+    pub path: String,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self { path: String::from("xatu.db") }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DiscordConfig {
+    /// Channel the report is posted to.
+    pub channel: u64,
+    pub format: ReportFormat,
+    /// Edit the same dashboard messages on every refresh instead of posting new ones.
+    pub dashboard: bool,
+    /// Show update dates as Discord timestamps rather than as text relative to the report date.
+    pub timestamps: bool,
+}
+
+impl Default for DiscordConfig {
+    fn default() -> Self {
+        Self { channel: 875622508026544148, format: ReportFormat::default(), dashboard: true, timestamps: false }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    /// A single markdown message, which fails past Discord's 2000 characters.
+    Markdown,
+    /// Embeds split across as many messages as needed.
+    #[default]
+    Embeds,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GithubConfig {
+    /// Repositories to report on, as `owner/name`.
+    pub repositories: Vec<Repository>,
+    /// Seconds between two refreshes of the report.
+    pub refresh_interval: u64,
+    pub api: Api,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Api {
+    /// Pages the issues and pulls lists, and runs four searches.
+    #[default]
+    Rest,
+    /// Pages issues and pull requests in larger queries, and gets the lists in a single one.
+    Graphql,
+}
+
+impl Default for GithubConfig {
+    fn default() -> Self {
+        Self {
+            repositories: vec![Repository::new("rh-hideout", "pokeemerald-expansion")],
+            refresh_interval: 400,
+            api: Api::default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LabelConfig {
+    /// Categories the open issues are counted in, by priority: an issue falls in the first one it matches.
+    pub categories: Vec<Category>,
+    /// Label of the issues listed as stale bugs.
+    pub bug: String,
+    /// Labels, or prefixes ending with `*`, whose issues are counted on their own.
+    pub breakdown: Vec<LabelPattern>,
+}
+
+impl Default for LabelConfig {
+    fn default() -> Self {
+        Self {
+            categories: vec![
+                Category::labelled("Unconfirmed Bugs", "status: unconfirmed"),
+                Category::labelled("Confirmed Bugs", "status: confirmed"),
+                Category::labelled("Feature Requests", "feature-request"),
+                Category::labelled("Questions", "question"),
+            ],
+            bug: String::from("bug"),
+            breakdown: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ListConfig {
+    /// Number of stale issues and pull requests listed.
+    pub stale: u8,
+    /// Number of recently created issues and pull requests listed.
+    pub recent: u8,
+}
+
+impl Default for ListConfig {
+    fn default() -> Self {
+        Self { stale: 3, recent: 3 }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RecordingConfig {
+    /// Record the GitHub responses of every refresh, to replay them with `xatu replay <run id>`.
+    pub enabled: bool,
+    pub dir: String,
+}
+
+impl Default for RecordingConfig {
+    fn default() -> Self {
+        Self { enabled: false, dir: String::from("recordings") }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReportConfig {
+    /// IANA timezone deciding where days start, for the stats windows and their search links.
+    pub timezone: Tz,
+    /// Windows the stats are counted over, one column each.
+    pub windows: Vec<Window>,
+}
+
+impl Default for ReportConfig {
+    fn default() -> Self {
+        Self { timezone: Tz::UTC, windows: Window::DEFAULT.to_vec() }
+    }
+}
+
+impl Config {
+    /// Loads the configuration at `path`, falling back to the defaults if the file does not exist.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let config = match fs::read_to_string(path) {
+            Ok(content) => Self::parse(&content)?,
+            Err(why) if why.kind() == io::ErrorKind::NotFound => {
+                println!("{} not found, using the default configuration", path.display());
+                Self::default()
+            },
+            Err(why) => return Err(ConfigError::Io(path.display().to_string(), why)),
+        };
+        Ok(config)
+    }
+
+    pub fn parse(content: &str) -> Result<Self, ConfigError> {
+        let config: Self = toml::from_str(content).map_err(ConfigError::Parse)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        let mut problems = Vec::new();
+        if !(7..=365).contains(&self.charts.days) {
+            problems.push(String::from("charts.days must be between 7 and 365"));
+        }
+        if self.database.path.trim().is_empty() {
+            problems.push(String::from("database.path must not be empty"));
+        }
+        if self.recording.dir.trim().is_empty() {
+            problems.push(String::from("recording.dir must not be empty"));
+        }
+        if self.recording.enabled && self.github.api != Api::Rest {
+            problems.push(String::from("recording.enabled needs github.api = \"rest\""));
+        }
+        if self.discord.channel == 0 {
+            problems.push(String::from("discord.channel must be a channel id"));
+        }
+        if self.github.repositories.is_empty() {
+            problems.push(String::from("github.repositories must list at least one repository"));
+        }
+        let mut seen = HashSet::new();
+        for repository in &self.github.repositories {
+            if !seen.insert(repository.to_string()) {
+                problems.push(format!("github.repositories lists {repository} more than once"));
+            }
+        }
+        if !(1..=MAX_WINDOWS).contains(&self.report.windows.len()) {
+            problems.push(format!("report.windows must list between 1 and {MAX_WINDOWS} windows"));
+        }
+        for (i, window) in self.report.windows.iter().enumerate() {
+            if self.report.windows[..i].contains(window) {
+                problems.push(format!("report.windows lists \"{window}\" more than once"));
+            }
+        }
+        if self.github.refresh_interval < 60 {
+            problems.push(String::from("github.refresh_interval must be at least 60 seconds"));
+        }
+        if self.labels.bug.trim().is_empty() {
+            problems.push(String::from("labels.bug must not be empty"));
+        }
+        if self.labels.categories.len() > MAX_CATEGORIES {
+            problems.push(format!("labels.categories must list at most {MAX_CATEGORIES} categories"));
+        }
+        for (i, category) in self.labels.categories.iter().enumerate() {
+            if category.name.trim().is_empty() {
+                problems.push(String::from("labels.categories must all have a name"));
+            } else if self.labels.categories[..i].iter().any(|c| c.name == category.name) {
+                problems.push(format!("labels.categories lists \"{}\" more than once", category.name));
+            }
+            if category.any.is_empty() && category.all.is_empty() && category.none.is_empty() {
+                problems.push(format!("labels.categories \"{}\" must list labels in any, all or none", category.name));
+            }
+        }
+        for (i, pattern) in self.labels.breakdown.iter().enumerate() {
+            if self.labels.breakdown[..i].contains(pattern) {
+                problems.push(format!("labels.breakdown lists \"{pattern}\" more than once"));
+            }
+        }
+        for (name, size) in [("stale", self.lists.stale), ("recent", self.lists.recent)] {
+            if !(1..=100).contains(&size) {
+                problems.push(format!("lists.{name} must be between 1 and 100"));
+            }
+        }
+
+        match problems.is_empty() {
+            true => Ok(()),
+            false => Err(ConfigError::Invalid(problems)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::*;
+
+    #[test]
+    fn empty_file_uses_defaults() {
+        let config = Config::parse("").unwrap();
+        assert_eq!(config.discord.channel, 875622508026544148);
+        assert_eq!(config.github.refresh_interval, 400);
+        assert_eq!(config.github.repositories, vec![Repository::new("rh-hideout", "pokeemerald-expansion")]);
+        assert_eq!(config.labels.categories[1], Category::labelled("Confirmed Bugs", "status: confirmed"));
+        assert_eq!(config.lists.stale, 3);
+    }
+
+    #[test]
+    fn example_file_is_valid() {
+        Config::parse(include_str!("../xatu.example.toml")).unwrap();
+    }
+
+    #[test]
+    fn partial_section_keeps_other_defaults() {
+        let config = Config::parse("[github]\nrepositories = [\"octo/cat\", \"octo/dog\"]\n[lists]\nrecent = 5\n").unwrap();
+        assert_eq!(config.github.repositories.len(), 2);
+        assert_eq!(config.github.refresh_interval, 400);
+        assert_eq!(config.lists.recent, 5);
+        assert_eq!(config.lists.stale, 3);
+    }
+
+    #[test]
+    fn invalid_values_are_all_reported() {
+        let Err(ConfigError::Invalid(problems)) = Config::parse("[github]\nrepositories = []\nrefresh_interval = 5\n[lists]\nstale = 0\n") else {
+            panic!("expected a validation error");
+        };
+        assert_eq!(problems.len(), 3);
+    }
+
+    #[test]
+    fn malformed_repository_is_rejected() {
+        assert!(matches!(Config::parse("[github]\nrepositories = [\"octocat\"]\n"), Err(ConfigError::Parse(..))));
+    }
+
+    #[test]
+    fn timezone_is_an_iana_name() {
+        assert_eq!(Config::parse("[report]\ntimezone = \"Europe/Paris\"\n").unwrap().report.timezone, chrono_tz::Europe::Paris);
+        assert!(matches!(Config::parse("[report]\ntimezone = \"Europe/Parsi\"\n"), Err(ConfigError::Parse(..))));
+    }
+
+    #[test]
+    fn windows_are_parsed_and_checked() {
+        let config = Config::parse("[report]\nwindows = [\"today\", \"this week\", \"last 90 days\", \"since release\"]\n").unwrap();
+        assert_eq!(config.report.windows, vec![Window::Today, Window::Week, Window::LastDays(90), Window::SinceRelease]);
+        assert!(matches!(Config::parse("[report]\nwindows = [\"fortnight\"]\n"), Err(ConfigError::Parse(..))));
+        assert!(matches!(Config::parse("[report]\nwindows = [\"today\", \"today\"]\n"), Err(ConfigError::Invalid(..))));
+        assert!(matches!(Config::parse("[report]\nwindows = []\n"), Err(ConfigError::Invalid(..))));
+    }
+
+    #[test]
+    fn label_breakdown_is_parsed_and_checked() {
+        let config = Config::parse("[labels]\nbreakdown = [\"bug\", \"type: *\"]\n").unwrap();
+        assert_eq!(config.labels.breakdown, vec![LabelPattern::Exact(String::from("bug")), LabelPattern::Prefix(String::from("type: "))]);
+        assert!(matches!(Config::parse("[labels]\nbreakdown = [\"\"]\n"), Err(ConfigError::Parse(..))));
+        assert!(matches!(Config::parse("[labels]\nbreakdown = [\"type: *\", \"type: *\"]\n"), Err(ConfigError::Invalid(..))));
+    }
+
+    #[test]
+    fn categories_are_parsed_and_checked() {
+        let config = Config::parse("[[labels.categories]]\nname = \"Bugs\"\nall = [\"bug\"]\nnone = [\"status: *\"]\n").unwrap();
+        assert_eq!(config.labels.categories, vec![Category {
+            name: String::from("Bugs"),
+            any: Vec::new(),
+            all: vec![LabelPattern::Exact(String::from("bug"))],
+            none: vec![LabelPattern::Prefix(String::from("status: "))],
+        }]);
+        let Err(ConfigError::Invalid(problems)) = Config::parse("[[labels.categories]]\nname = \"Bugs\"\nany = [\"bug\"]\n[[labels.categories]]\nname = \"Bugs\"\n") else {
+            panic!("expected a validation error");
+        };
+        assert_eq!(problems, ["labels.categories lists \"Bugs\" more than once", "labels.categories \"Bugs\" must list labels in any, all or none"]);
+        assert!(matches!(Config::parse("[[labels.categories]]\nname = \"Bugs\"\nsome = [\"bug\"]\n"), Err(ConfigError::Parse(..))));
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        assert!(matches!(Config::parse("[discord]\nchanel = 1\n"), Err(ConfigError::Parse(..))));
+    }
+}
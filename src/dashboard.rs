@@ -0,0 +1,119 @@
+use serenity::{all::{ChannelId, CreateAttachment, CreateEmbed, CreateMessage, EditMessage, Http, MessageId}, http::StatusCode};
+
+use crate::{charts::CHART_FILENAME, config::ReportFormat, db::Database, github::{self, GithubData}};
+
+/// One Discord message of a rendered report.
+pub struct ReportMessage {
+    content: String,
+    embeds: Vec<CreateEmbed>,
+    chart: Option<Vec<u8>>,
+}
+
+impl ReportMessage {
+    pub fn chart(content: String, png: Vec<u8>) -> Self {
+        Self { content, embeds: Vec::new(), chart: Some(png) }
+    }
+
+    fn create(&self) -> CreateMessage {
+        let message = CreateMessage::new().content(&self.content).embeds(self.embeds.clone());
+        match &self.chart {
+            Some(png) => message.add_file(CreateAttachment::bytes(png.clone(), CHART_FILENAME)),
+            None => message,
+        }
+    }
+
+    fn edit(&self) -> EditMessage {
+        let message = EditMessage::new().content(&self.content).embeds(self.embeds.clone());
+        match &self.chart {
+            // Replaces the previous chart
+            Some(png) => message.new_attachment(CreateAttachment::bytes(png.clone(), CHART_FILENAME)),
+            // Drops the chart of a slot that held one before
+            None => message.remove_all_attachments(),
+        }
+    }
+}
+
+pub fn render(reports: &[GithubData], format: ReportFormat) -> Vec<ReportMessage> {
+    reports.iter().flat_map(|report| match format {
+        ReportFormat::Markdown => vec![ReportMessage { content: report.render(), embeds: Vec::new(), chart: None }],
+        ReportFormat::Embeds => github::paginate(report.render_embeds()).into_iter()
+            .map(|embeds| ReportMessage { content: String::new(), embeds: embeds.iter().map(|e| e.build()).collect(), chart: None })
+            .collect(),
+    }).collect()
+}
+
+/// Posts the messages as new messages in `channel`.
+pub async fn post(http: &Http, channel: ChannelId, messages: &[ReportMessage]) {
+    for message in messages {
+        if let Err(why) = channel.send_message(http, message.create()).await {
+            eprintln!("Error sending message in channel: {why:?}");
+        }
+    }
+}
+
+/// Edits the dashboard messages of `channel` in place, creating the missing ones and deleting
+/// the ones left over from a longer report.
+pub async fn update(http: &Http, database: &Database, channel: ChannelId, messages: &[ReportMessage]) {
+    let existing = match database.dashboard_messages(channel.get()) {
+        Ok(existing) => existing,
+        Err(why) => {
+            eprintln!("Error reading dashboard messages of {channel}: {why}");
+            Vec::new()
+        }
+    };
+
+    for (slot, message) in messages.iter().enumerate() {
+        if let Some((_, message_id)) = existing.iter().find(|(s, _)| *s == slot) {
+            match channel.edit_message(http, MessageId::new(*message_id), message.edit()).await {
+                Ok(_) => continue,
+                Err(serenity::Error::Http(why)) if why.status_code() == Some(StatusCode::NOT_FOUND) => {
+                    println!("Dashboard message {message_id} was deleted, sending a new one");
+                },
+                Err(why) => {
+                    eprintln!("Error editing dashboard message {message_id}: {why:?}");
+                    continue;
+                },
+            }
+        }
+
+        match channel.send_message(http, message.create()).await {
+            Ok(sent) => {
+                if let Err(why) = database.set_dashboard_message(channel.get(), slot, sent.id.get()) {
+                    eprintln!("Error saving dashboard message {}: {why}", sent.id);
+                }
+                if slot == 0 {
+                    if let Err(why) = sent.pin(http).await {
+                        eprintln!("Error pinning dashboard message {}: {why:?}", sent.id);
+                    }
+                }
+            },
+            Err(why) => eprintln!("Error sending message in channel: {why:?}"),
+        }
+    }
+
+    for (slot, message_id) in existing.into_iter().filter(|(slot, _)| *slot >= messages.len()) {
+        if let Err(why) = channel.delete_message(http, MessageId::new(message_id)).await {
+            eprintln!("Error deleting dashboard message {message_id}: {why:?}");
+        }
+        if let Err(why) = database.remove_dashboard_message(channel.get(), slot) {
+            eprintln!("Error removing dashboard message {message_id}: {why}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dashboard::*;
+
+    #[test]
+    fn edits_replace_or_drop_the_chart() {
+        let chart = ReportMessage::chart(String::from("**Trends**"), vec![1, 2, 3]);
+        assert_eq!(chart.edit(), EditMessage::new().content("**Trends**").embeds(Vec::new())
+            .new_attachment(CreateAttachment::bytes(vec![1, 2, 3], CHART_FILENAME)));
+
+        // The slot of the chart now holds a report
+        let report = ReportMessage { content: String::from("# Report"), embeds: Vec::new(), chart: None };
+        assert_eq!(report.edit(), EditMessage::new().content("# Report").embeds(Vec::new()).remove_all_attachments());
+        assert_ne!(report.edit(), EditMessage::new().content("# Report").embeds(Vec::new()));
+    }
+}
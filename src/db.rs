@@ -0,0 +1,586 @@
+use std::{collections::HashMap, path::Path, sync::Mutex};
+
+use chrono::{DateTime, Utc};
+use octocrab::models::IssueState;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+use crate::github::{ParsedIssue, ParsedPR, ParsedReview, Repository};
+
+static MIGRATIONS: &[&str] = &[
+    "CREATE TABLE issues (
+        repository TEXT NOT NULL,
+        number INTEGER NOT NULL,
+        user TEXT NOT NULL,
+        url TEXT NOT NULL,
+        title TEXT NOT NULL,
+        state TEXT NOT NULL,
+        created_at TEXT NOT NULL,
+        updated_at TEXT NOT NULL,
+        closed_at TEXT,
+        labels TEXT NOT NULL,
+        PRIMARY KEY (repository, number)
+    );
+    CREATE TABLE pull_requests (
+        repository TEXT NOT NULL,
+        number INTEGER NOT NULL,
+        user TEXT NOT NULL,
+        url TEXT NOT NULL,
+        title TEXT NOT NULL,
+        state TEXT NOT NULL,
+        open_state TEXT NOT NULL,
+        created_at TEXT NOT NULL,
+        updated_at TEXT NOT NULL,
+        closed_at TEXT,
+        labels TEXT NOT NULL,
+        PRIMARY KEY (repository, number)
+    );
+    CREATE TABLE sync_state (
+        repository TEXT NOT NULL,
+        kind TEXT NOT NULL,
+        updated_at TEXT NOT NULL,
+        PRIMARY KEY (repository, kind)
+    );",
+    "CREATE TABLE snapshots (
+        scope TEXT NOT NULL,
+        taken_at TEXT NOT NULL,
+        open_issues INTEGER NOT NULL,
+        confirmed_issues INTEGER NOT NULL,
+        unconfirmed_issues INTEGER NOT NULL,
+        feature_requests INTEGER NOT NULL,
+        open_pull_requests INTEGER NOT NULL,
+        ready_pull_requests INTEGER NOT NULL,
+        draft_pull_requests INTEGER NOT NULL,
+        PRIMARY KEY (scope, taken_at)
+    );",
+    "CREATE TABLE dashboard_messages (
+        channel_id INTEGER NOT NULL,
+        slot INTEGER NOT NULL,
+        message_id INTEGER NOT NULL,
+        PRIMARY KEY (channel_id, slot)
+    );",
+    "CREATE TABLE meta (
+        key TEXT PRIMARY KEY NOT NULL,
+        value TEXT NOT NULL
+    );",
+    "ALTER TABLE pull_requests ADD COLUMN requested_reviewers TEXT NOT NULL DEFAULT '[]';
+    CREATE TABLE reviews (
+        repository TEXT NOT NULL,
+        pull_request INTEGER NOT NULL,
+        reviewer TEXT NOT NULL,
+        state TEXT NOT NULL,
+        submitted_at TEXT NOT NULL
+    );
+    CREATE INDEX reviews_by_pull_request ON reviews (repository, pull_request);
+    -- Syncs every pull request again for its requested reviewers, but only fetches the reviews of the open ones and
+    -- the ones updated since the previous sync
+    UPDATE sync_state SET kind = 'reviews' WHERE kind = 'pull_requests';",
+    // Drafts cannot be merged, so closed drafts were closed without being merged
+    "UPDATE pull_requests SET state = CASE
+        WHEN state = 'open' THEN 'ready'
+        WHEN state = 'cancelled' THEN 'closed'
+        WHEN state = 'draft' AND open_state = 'closed' THEN 'closed'
+        ELSE state
+    END;",
+    // The three fixed counts become the default categories, and the open issues in none of them uncategorised
+    "ALTER TABLE snapshots ADD COLUMN categories TEXT NOT NULL DEFAULT '[]';
+    ALTER TABLE snapshots ADD COLUMN uncategorised_issues INTEGER NOT NULL DEFAULT 0;
+    UPDATE snapshots SET
+        categories = json_array(
+            json_array('Unconfirmed Bugs', unconfirmed_issues),
+            json_array('Confirmed Bugs', confirmed_issues),
+            json_array('Feature Requests', feature_requests)),
+        uncategorised_issues = max(open_issues - unconfirmed_issues - confirmed_issues - feature_requests, 0);
+    ALTER TABLE snapshots DROP COLUMN confirmed_issues;
+    ALTER TABLE snapshots DROP COLUMN unconfirmed_issues;
+    ALTER TABLE snapshots DROP COLUMN feature_requests;",
+];
+
+#[derive(Clone, Copy, Debug)]
+pub enum SyncKind {
+    Issues,
+    PullRequests,
+    /// Last sync of the pull requests before they were synced again for their reviews, until that is done.
+    Reviews,
+}
+
+impl SyncKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Issues => "issues",
+            Self::PullRequests => "pull_requests",
+            Self::Reviews => "reviews",
+        }
+    }
+}
+
+/// Headline numbers of a report, kept to follow the backlog over time.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Snapshot {
+    /// Repositories the report covers, as `owner/name` joined by commas.
+    pub scope: String,
+    pub taken_at: DateTime<Utc>,
+    pub open_issues: usize,
+    /// Open issues of each category, in order.
+    pub categories: Vec<(String, usize)>,
+    pub uncategorised_issues: usize,
+    pub open_pull_requests: usize,
+    pub ready_pull_requests: usize,
+    pub draft_pull_requests: usize,
+}
+
+impl Snapshot {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            scope: row.get(0)?,
+            taken_at: row.get(1)?,
+            open_issues: row.get(2)?,
+            categories: serde_json::from_str(&row.get::<_, String>(3)?).map_err(|why| conversion_error(3, why.to_string()))?,
+            uncategorised_issues: row.get(4)?,
+            open_pull_requests: row.get(5)?,
+            ready_pull_requests: row.get(6)?,
+            draft_pull_requests: row.get(7)?,
+        })
+    }
+}
+
+static SNAPSHOT_COLUMNS: &str = "scope, taken_at, open_issues, categories, uncategorised_issues, open_pull_requests, ready_pull_requests, draft_pull_requests";
+
+pub struct Database {
+    connection: Mutex<Connection>,
+}
+
+impl Database {
+    pub fn open(path: impl AsRef<Path>) -> rusqlite::Result<Self> {
+        Self::init(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> rusqlite::Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(mut connection: Connection) -> rusqlite::Result<Self> {
+        let version: usize = connection.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        let transaction = connection.transaction()?;
+        for migration in MIGRATIONS.iter().skip(version) {
+            transaction.execute_batch(migration)?;
+        }
+        transaction.pragma_update(None, "user_version", MIGRATIONS.len())?;
+        transaction.commit()?;
+        Ok(Self { connection: Mutex::new(connection) })
+    }
+
+    /// Most recent `updated_at` stored for `kind`, to resume syncing from.
+    pub fn last_sync(&self, repository: &Repository, kind: SyncKind) -> rusqlite::Result<Option<DateTime<Utc>>> {
+        self.connection.lock().unwrap()
+            .query_row("SELECT updated_at FROM sync_state WHERE repository = ?1 AND kind = ?2",
+                params![repository.to_string(), kind.as_str()], |row| row.get(0))
+            .optional()
+    }
+
+    pub fn save_issues(&self, repository: &Repository, issues: &[ParsedIssue]) -> rusqlite::Result<()> {
+        let mut connection = self.connection.lock().unwrap();
+        let transaction = connection.transaction()?;
+        insert_issues(&transaction, repository, issues)?;
+        transaction.commit()
+    }
+
+    /// Replaces every issue of `repository` with `issues`, dropping the deleted and transferred ones.
+    pub fn replace_issues(&self, repository: &Repository, issues: &[ParsedIssue]) -> rusqlite::Result<()> {
+        let mut connection = self.connection.lock().unwrap();
+        let transaction = connection.transaction()?;
+        transaction.execute("DELETE FROM issues WHERE repository = ?1", [repository.to_string()])?;
+        insert_issues(&transaction, repository, issues)?;
+        transaction.commit()
+    }
+
+    /// Saves the pull requests along with their reviews, which replace the ones saved before.
+    pub fn save_pull_requests(&self, repository: &Repository, pull_requests: &[ParsedPR]) -> rusqlite::Result<()> {
+        let mut connection = self.connection.lock().unwrap();
+        let transaction = connection.transaction()?;
+        {
+            let mut statement = transaction.prepare_cached(
+                "INSERT OR REPLACE INTO pull_requests (repository, number, user, url, title, state, open_state, created_at, updated_at, closed_at, labels, requested_reviewers)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)")?;
+            let mut delete_reviews = transaction.prepare_cached("DELETE FROM reviews WHERE repository = ?1 AND pull_request = ?2")?;
+            let mut insert_review = transaction.prepare_cached(
+                "INSERT INTO reviews (repository, pull_request, reviewer, state, submitted_at) VALUES (?1, ?2, ?3, ?4, ?5)")?;
+            for pr in pull_requests {
+                statement.execute(params![
+                    repository.to_string(), pr.id, pr.user, pr.url, pr.title, pr.state.as_str(), issue_state_str(&pr.open_state),
+                    pr.creation_date, pr.updated_date, pr.closed_date, strings_to_json(&pr.labels), strings_to_json(&pr.requested_reviewers),
+                ])?;
+                delete_reviews.execute(params![repository.to_string(), pr.id])?;
+                for review in &pr.reviews {
+                    insert_review.execute(params![repository.to_string(), pr.id, review.reviewer, review.state.as_str(), review.submitted_date])?;
+                }
+            }
+        }
+        if let Some(updated_at) = pull_requests.iter().map(|p| p.updated_date).max() {
+            update_sync(&transaction, repository, SyncKind::PullRequests, updated_at)?;
+        }
+        transaction.execute("DELETE FROM sync_state WHERE repository = ?1 AND kind = ?2", params![repository.to_string(), SyncKind::Reviews.as_str()])?;
+        transaction.commit()
+    }
+
+    pub fn issues(&self, repository: &Repository) -> rusqlite::Result<Vec<ParsedIssue>> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare_cached(
+            "SELECT number, user, url, title, state, created_at, updated_at, closed_at, labels FROM issues WHERE repository = ?1")?;
+        let issues = statement.query_map([repository.to_string()], |row| {
+            Ok(ParsedIssue {
+                id: row.get(0)?,
+                user: row.get(1)?,
+                url: row.get(2)?,
+                title: row.get(3)?,
+                state: issue_state_from_row(row, 4)?,
+                creation_date: row.get(5)?,
+                updated_date: row.get(6)?,
+                closed_date: row.get(7)?,
+                labels: strings_from_row(row, 8)?,
+            })
+        })?;
+        issues.collect()
+    }
+
+    pub fn pull_requests(&self, repository: &Repository) -> rusqlite::Result<Vec<ParsedPR>> {
+        let mut reviews = self.reviews(repository)?;
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare_cached(
+            "SELECT number, user, url, title, state, open_state, created_at, updated_at, closed_at, labels, requested_reviewers FROM pull_requests WHERE repository = ?1")?;
+        let pull_requests = statement.query_map([repository.to_string()], |row| {
+            Ok(ParsedPR {
+                id: row.get(0)?,
+                user: row.get(1)?,
+                url: row.get(2)?,
+                title: row.get(3)?,
+                state: row.get::<_, String>(4)?.parse().map_err(|why: String| conversion_error(4, why))?,
+                open_state: issue_state_from_row(row, 5)?,
+                creation_date: row.get(6)?,
+                updated_date: row.get(7)?,
+                closed_date: row.get(8)?,
+                labels: strings_from_row(row, 9)?,
+                requested_reviewers: strings_from_row(row, 10)?,
+                reviews: Vec::new(),
+            })
+        })?;
+        pull_requests
+            .map(|pr| pr.map(|pr| ParsedPR { reviews: reviews.remove(&pr.id).unwrap_or_default(), ..pr }))
+            .collect()
+    }
+
+    /// Reviews of the pull requests of `repository` by number, oldest first.
+    fn reviews(&self, repository: &Repository) -> rusqlite::Result<HashMap<u64, Vec<ParsedReview>>> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare_cached(
+            "SELECT pull_request, reviewer, state, submitted_at FROM reviews WHERE repository = ?1 ORDER BY submitted_at")?;
+        let mut reviews: HashMap<u64, Vec<ParsedReview>> = HashMap::new();
+        for review in statement.query_map([repository.to_string()], |row| {
+            Ok((row.get(0)?, ParsedReview {
+                reviewer: row.get(1)?,
+                state: row.get::<_, String>(2)?.parse().map_err(|why: String| conversion_error(2, why))?,
+                submitted_date: row.get(3)?,
+            }))
+        })? {
+            let (number, review) = review?;
+            reviews.entry(number).or_default().push(review);
+        }
+        Ok(reviews)
+    }
+
+    pub fn save_snapshot(&self, snapshot: &Snapshot) -> rusqlite::Result<()> {
+        self.connection.lock().unwrap().execute(
+            &format!("INSERT OR REPLACE INTO snapshots ({SNAPSHOT_COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)"),
+            params![
+                snapshot.scope, snapshot.taken_at, snapshot.open_issues, serde_json::to_string(&snapshot.categories).unwrap(),
+                snapshot.uncategorised_issues, snapshot.open_pull_requests, snapshot.ready_pull_requests, snapshot.draft_pull_requests,
+            ])?;
+        Ok(())
+    }
+
+    /// Latest snapshot of `scope` taken at or before `at`.
+    pub fn snapshot_at(&self, scope: &str, at: DateTime<Utc>) -> rusqlite::Result<Option<Snapshot>> {
+        self.connection.lock().unwrap()
+            .query_row(&format!("SELECT {SNAPSHOT_COLUMNS} FROM snapshots WHERE scope = ?1 AND taken_at <= ?2 ORDER BY taken_at DESC LIMIT 1"),
+                params![scope, at], Snapshot::from_row)
+            .optional()
+    }
+
+    /// Ids of the dashboard messages of `channel`, ordered by slot.
+    pub fn dashboard_messages(&self, channel: u64) -> rusqlite::Result<Vec<(usize, u64)>> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare_cached("SELECT slot, message_id FROM dashboard_messages WHERE channel_id = ?1 ORDER BY slot")?;
+        let messages = statement.query_map([channel], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        messages.collect()
+    }
+
+    pub fn set_dashboard_message(&self, channel: u64, slot: usize, message: u64) -> rusqlite::Result<()> {
+        self.connection.lock().unwrap().execute(
+            "INSERT OR REPLACE INTO dashboard_messages (channel_id, slot, message_id) VALUES (?1, ?2, ?3)",
+            params![channel, slot, message])?;
+        Ok(())
+    }
+
+    pub fn remove_dashboard_message(&self, channel: u64, slot: usize) -> rusqlite::Result<()> {
+        self.connection.lock().unwrap().execute(
+            "DELETE FROM dashboard_messages WHERE channel_id = ?1 AND slot = ?2",
+            params![channel, slot])?;
+        Ok(())
+    }
+
+    pub fn meta(&self, key: &str) -> rusqlite::Result<Option<String>> {
+        self.connection.lock().unwrap()
+            .query_row("SELECT value FROM meta WHERE key = ?1", [key], |row| row.get(0))
+            .optional()
+    }
+
+    pub fn set_meta(&self, key: &str, value: &str) -> rusqlite::Result<()> {
+        self.connection.lock().unwrap().execute("INSERT OR REPLACE INTO meta (key, value) VALUES (?1, ?2)", [key, value])?;
+        Ok(())
+    }
+}
+
+fn insert_issues(connection: &Connection, repository: &Repository, issues: &[ParsedIssue]) -> rusqlite::Result<()> {
+    let mut statement = connection.prepare_cached(
+        "INSERT OR REPLACE INTO issues (repository, number, user, url, title, state, created_at, updated_at, closed_at, labels)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)")?;
+    for issue in issues {
+        statement.execute(params![
+            repository.to_string(), issue.id, issue.user, issue.url, issue.title, issue_state_str(&issue.state),
+            issue.creation_date, issue.updated_date, issue.closed_date, strings_to_json(&issue.labels),
+        ])?;
+    }
+    if let Some(updated_at) = issues.iter().map(|i| i.updated_date).max() {
+        update_sync(connection, repository, SyncKind::Issues, updated_at)?;
+    }
+    Ok(())
+}
+
+fn update_sync(connection: &Connection, repository: &Repository, kind: SyncKind, updated_at: DateTime<Utc>) -> rusqlite::Result<()> {
+    connection.execute(
+        "INSERT INTO sync_state (repository, kind, updated_at) VALUES (?1, ?2, ?3)
+        ON CONFLICT (repository, kind) DO UPDATE SET updated_at = MAX(updated_at, excluded.updated_at)",
+        params![repository.to_string(), kind.as_str(), updated_at])?;
+    Ok(())
+}
+
+fn issue_state_str(state: &IssueState) -> &'static str {
+    match state {
+        IssueState::Closed => "closed",
+        _ => "open",
+    }
+}
+
+fn issue_state_from_row(row: &Row, index: usize) -> rusqlite::Result<IssueState> {
+    match row.get::<_, String>(index)?.as_str() {
+        "open" => Ok(IssueState::Open),
+        "closed" => Ok(IssueState::Closed),
+        state => Err(conversion_error(index, format!("unknown issue state \"{state}\""))),
+    }
+}
+
+fn strings_to_json(strings: &[String]) -> String {
+    serde_json::to_string(strings).unwrap()
+}
+
+fn strings_from_row(row: &Row, index: usize) -> rusqlite::Result<Vec<String>> {
+    serde_json::from_str(&row.get::<_, String>(index)?)
+        .map_err(|why| rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, Box::new(why)))
+}
+
+fn conversion_error(index: usize, why: String) -> rusqlite::Error {
+    rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, why.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use crate::{db::*, github::{test_support, PRState, ReviewState}};
+
+    fn issue(id: u64, updated_day: u32) -> ParsedIssue {
+        ParsedIssue {
+            user: String::from("octocat"),
+            id,
+            url: format!("https://github.com/octo/cat/issues/{id}"),
+            title: format!("Issue {id}"),
+            state: IssueState::Open,
+            creation_date: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+            updated_date: Utc.with_ymd_and_hms(2024, 1, updated_day, 12, 0, 0).unwrap(),
+            closed_date: None,
+            labels: vec![String::from("status: confirmed"), String::from("bug")],
+        }
+    }
+
+    #[test]
+    fn issues_round_trip_and_update() {
+        let database = Database::open_in_memory().unwrap();
+        let repository = Repository::new("octo", "cat");
+        assert_eq!(database.last_sync(&repository, SyncKind::Issues).unwrap(), None);
+
+        database.save_issues(&repository, &[issue(1, 2), issue(2, 5)]).unwrap();
+        let mut closed = issue(1, 9);
+        closed.state = IssueState::Closed;
+        closed.closed_date = Some(closed.updated_date);
+        database.save_issues(&repository, &[closed]).unwrap();
+
+        let mut issues = database.issues(&repository).unwrap();
+        issues.sort_by_key(|i| i.id);
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].state, IssueState::Closed);
+        assert_eq!(issues[0].closed_date, Some(Utc.with_ymd_and_hms(2024, 1, 9, 12, 0, 0).unwrap()));
+        assert_eq!(issues[1].labels, vec!["status: confirmed", "bug"]);
+        assert_eq!(database.last_sync(&repository, SyncKind::Issues).unwrap(), Some(Utc.with_ymd_and_hms(2024, 1, 9, 12, 0, 0).unwrap()));
+        assert!(database.issues(&Repository::new("octo", "dog")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn replaced_issues_drop_the_missing_ones() {
+        let database = Database::open_in_memory().unwrap();
+        let (cat, dog) = (Repository::new("octo", "cat"), Repository::new("octo", "dog"));
+        database.save_issues(&cat, &[issue(1, 2), issue(2, 5)]).unwrap();
+        database.save_issues(&dog, &[issue(1, 3)]).unwrap();
+
+        // #1 was transferred away
+        database.replace_issues(&cat, &[issue(2, 6)]).unwrap();
+        assert_eq!(database.issues(&cat).unwrap().iter().map(|i| i.id).collect::<Vec<u64>>(), vec![2]);
+        assert_eq!(database.issues(&dog).unwrap().len(), 1);
+        assert_eq!(database.last_sync(&cat, SyncKind::Issues).unwrap(), Some(Utc.with_ymd_and_hms(2024, 1, 6, 12, 0, 0).unwrap()));
+    }
+
+    #[test]
+    fn pull_requests_round_trip() {
+        let database = Database::open_in_memory().unwrap();
+        let repository = Repository::new("octo", "cat");
+        let issue = issue(3, 4);
+        let pr = ParsedPR {
+            user: issue.user,
+            id: issue.id,
+            url: issue.url,
+            title: issue.title,
+            state: PRState::Merged,
+            open_state: IssueState::Closed,
+            creation_date: issue.creation_date,
+            updated_date: issue.updated_date,
+            closed_date: Some(issue.updated_date),
+            labels: Vec::new(),
+            requested_reviewers: vec![String::from("octo/reviewers")],
+            reviews: vec![ParsedReview { reviewer: String::from("mew"), state: ReviewState::Commented, submitted_date: issue.creation_date }],
+        };
+        database.save_pull_requests(&repository, std::slice::from_ref(&pr)).unwrap();
+        let approval = ParsedReview { reviewer: String::from("mew"), state: ReviewState::Approved, submitted_date: issue.updated_date };
+        let reviewed = ParsedPR { reviews: vec![pr.reviews[0].clone(), approval.clone()], ..pr };
+        database.save_pull_requests(&repository, &[reviewed]).unwrap();
+
+        let pull_requests = database.pull_requests(&repository).unwrap();
+        assert_eq!(pull_requests.len(), 1);
+        assert_eq!(pull_requests[0].state, PRState::Merged);
+        assert_eq!(pull_requests[0].requested_reviewers, vec!["octo/reviewers"]);
+        assert_eq!(pull_requests[0].reviews.len(), 2);
+        assert_eq!(pull_requests[0].reviews[1], approval);
+        assert_eq!(pull_requests[0].closed_date, Some(issue.updated_date));
+        assert_eq!(database.last_sync(&repository, SyncKind::Issues).unwrap(), None);
+        assert_eq!(database.last_sync(&repository, SyncKind::PullRequests).unwrap(), Some(issue.updated_date));
+    }
+
+    #[test]
+    fn pull_requests_are_synced_again_for_their_reviews() {
+        let connection = Connection::open_in_memory().unwrap();
+        let before_reviews = 4;
+        connection.execute_batch(&MIGRATIONS[..before_reviews].join("\n")).unwrap();
+        connection.pragma_update(None, "user_version", before_reviews).unwrap();
+        connection.execute("INSERT INTO sync_state (repository, kind, updated_at) VALUES ('octo/cat', 'pull_requests', '2026-03-01T00:00:00Z')", []).unwrap();
+
+        let database = Database::init(connection).unwrap();
+        let repository = Repository::new("octo", "cat");
+        let synced = Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap();
+        assert_eq!(database.last_sync(&repository, SyncKind::PullRequests).unwrap(), None);
+        assert_eq!(database.last_sync(&repository, SyncKind::Reviews).unwrap(), Some(synced));
+
+        let pr = test_support::pull_request(synced, None, PRState::Ready);
+        database.save_pull_requests(&repository, &[pr]).unwrap();
+        assert_eq!(database.last_sync(&repository, SyncKind::PullRequests).unwrap(), Some(synced));
+        assert_eq!(database.last_sync(&repository, SyncKind::Reviews).unwrap(), None);
+    }
+
+    #[test]
+    fn pull_request_states_are_migrated() {
+        let connection = Connection::open_in_memory().unwrap();
+        let before_states = 5;
+        connection.execute_batch(&MIGRATIONS[..before_states].join("\n")).unwrap();
+        connection.pragma_update(None, "user_version", before_states).unwrap();
+        for (number, state, open_state) in [(1, "open", "open"), (2, "draft", "open"), (3, "draft", "closed"), (4, "cancelled", "closed"), (5, "merged", "closed")] {
+            connection.execute(
+                "INSERT INTO pull_requests (repository, number, user, url, title, state, open_state, created_at, updated_at, labels)
+                VALUES ('octo/cat', ?1, 'octocat', '', '', ?2, ?3, '2026-03-01T00:00:00Z', '2026-03-01T00:00:00Z', '[]')",
+                params![number, state, open_state]).unwrap();
+        }
+
+        let database = Database::init(connection).unwrap();
+        let mut pull_requests = database.pull_requests(&Repository::new("octo", "cat")).unwrap();
+        pull_requests.sort_by_key(|p| p.id);
+        assert_eq!(pull_requests.iter().map(|p| p.state).collect::<Vec<PRState>>(),
+            vec![PRState::Ready, PRState::Draft, PRState::Closed, PRState::Closed, PRState::Merged]);
+    }
+
+    #[test]
+    fn snapshot_counts_become_categories() {
+        let connection = Connection::open_in_memory().unwrap();
+        let before_categories = MIGRATIONS.len() - 1;
+        connection.execute_batch(&MIGRATIONS[..before_categories].join("\n")).unwrap();
+        connection.pragma_update(None, "user_version", before_categories).unwrap();
+        connection.execute(
+            "INSERT INTO snapshots (scope, taken_at, open_issues, confirmed_issues, unconfirmed_issues, feature_requests, open_pull_requests, ready_pull_requests, draft_pull_requests)
+            VALUES ('octo/cat', '2026-03-01T00:00:00Z', 7, 1, 2, 3, 4, 3, 1)", []).unwrap();
+
+        let database = Database::init(connection).unwrap();
+        let snapshot = database.snapshot_at("octo/cat", Utc.with_ymd_and_hms(2026, 3, 2, 0, 0, 0).unwrap()).unwrap().unwrap();
+        assert_eq!(snapshot.categories, vec![
+            (String::from("Unconfirmed Bugs"), 2),
+            (String::from("Confirmed Bugs"), 1),
+            (String::from("Feature Requests"), 3),
+        ]);
+        assert_eq!((snapshot.open_issues, snapshot.uncategorised_issues, snapshot.draft_pull_requests), (7, 1, 1));
+    }
+
+    #[test]
+    fn snapshot_at_picks_latest_before() {
+        let database = Database::open_in_memory().unwrap();
+        for (day, confirmed_issues) in [(1, 10), (2, 12), (4, 7)] {
+            database.save_snapshot(&Snapshot {
+                scope: String::from("octo/cat"),
+                taken_at: Utc.with_ymd_and_hms(2026, 3, day, 8, 0, 0).unwrap(),
+                categories: vec![(String::from("Confirmed Bugs"), confirmed_issues)],
+                ..Snapshot::default()
+            }).unwrap();
+        }
+
+        let end_of_day = |day| Utc.with_ymd_and_hms(2026, 3, day, 23, 59, 59).unwrap();
+        assert_eq!(database.snapshot_at("octo/cat", end_of_day(3)).unwrap().unwrap().categories, vec![(String::from("Confirmed Bugs"), 12)]);
+        assert_eq!(database.snapshot_at("octo/cat", end_of_day(4)).unwrap().unwrap().categories, vec![(String::from("Confirmed Bugs"), 7)]);
+        assert_eq!(database.snapshot_at("octo/dog", end_of_day(4)).unwrap(), None);
+        assert_eq!(database.snapshot_at("octo/cat", Utc.with_ymd_and_hms(2026, 2, 28, 0, 0, 0).unwrap()).unwrap(), None);
+    }
+
+    #[test]
+    fn dashboard_messages_by_channel() {
+        let database = Database::open_in_memory().unwrap();
+        database.set_dashboard_message(1, 1, 11).unwrap();
+        database.set_dashboard_message(1, 0, 10).unwrap();
+        database.set_dashboard_message(2, 0, 20).unwrap();
+        database.set_dashboard_message(1, 1, 12).unwrap();
+        assert_eq!(database.dashboard_messages(1).unwrap(), vec![(0, 10), (1, 12)]);
+
+        database.remove_dashboard_message(1, 0).unwrap();
+        assert_eq!(database.dashboard_messages(1).unwrap(), vec![(1, 12)]);
+        assert_eq!(database.dashboard_messages(2).unwrap(), vec![(0, 20)]);
+    }
+
+    #[test]
+    fn meta_values_are_replaced() {
+        let database = Database::open_in_memory().unwrap();
+        assert_eq!(database.meta("key").unwrap(), None);
+        database.set_meta("key", "first").unwrap();
+        database.set_meta("key", "second").unwrap();
+        assert_eq!(database.meta("key").unwrap().as_deref(), Some("second"));
+    }
+}
@@ -0,0 +1,35 @@
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+
+use crate::{db::Database, github::GithubData};
+
+pub static GIST_FILENAME: &str = "xatu-report.md";
+static LAST_UPLOAD_KEY: &str = "gist_last_upload";
+
+/// Uploads the markdown reports to the gist, at most once per day in `timezone`.
+pub async fn upload_daily(database: &Database, gist_id: &str, reports: &[GithubData], now: DateTime<Utc>, timezone: Tz) {
+    let today = now.with_timezone(&timezone).date_naive().to_string();
+    match database.meta(LAST_UPLOAD_KEY) {
+        Ok(Some(last_upload)) if last_upload == today => return,
+        Ok(_) => {},
+        Err(why) => eprintln!("Error reading the last gist upload date: {why}"),
+    }
+
+    let content = reports.iter().map(|r| r.render_plain()).collect::<Vec<String>>().join("\n\n---\n\n");
+    let description = format!("Xatu report for {} on {today}", reports.last().map(|r| r.name()).unwrap_or_default());
+    match octocrab::instance().gists().update(gist_id)
+        .description(description)
+        .file(GIST_FILENAME)
+        .with_content(content)
+        .send().await
+    {
+        Ok(gist) => {
+            // Every update is a new revision, earlier reports stay available in the gist history
+            println!("Report uploaded to {} (history at {}/revisions)", gist.html_url, gist.html_url);
+            if let Err(why) = database.set_meta(LAST_UPLOAD_KEY, &today) {
+                eprintln!("Error saving the gist upload date: {why}");
+            }
+        },
+        Err(why) => eprintln!("Error uploading the report to gist {gist_id}, retrying on the next refresh: {why}"),
+    }
+}
@@ -1,137 +1,393 @@
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::{collections::{BTreeMap, HashMap}, fmt, ops::AddAssign, str::FromStr, sync::Arc};
 
-use chrono::{DateTime, NaiveDate, Utc};
-use octocrab::models::{self, issues::Issue, pulls::PullRequest, IssueState, Label};
+use chrono::{DateTime, NaiveTime, TimeDelta, Utc};
+use chrono_tz::Tz;
+use octocrab::models::{issues::Issue, pulls::{self, PullRequest, Review}, IssueState, Label};
+use serde::{Deserialize, Serialize};
 
-use crate::utils::format_timestamp_since;
+use crate::{clock::Clock, config::Config, db::{Database, Snapshot, SyncKind}, utils::{encode_query, format_duration, format_timestamp_since, Timestamps}};
+
+mod embeds;
+mod error;
+mod graphql;
+mod labels;
+mod latency;
+mod rate_limit;
+mod reviews;
+mod series;
+mod source;
+#[cfg(test)]
+pub mod test_support;
+mod window;
+
+pub use embeds::{fit_message, paginate};
+pub use error::FetchError;
+pub use graphql::GraphqlSource;
+pub use labels::{Category, LabelPattern};
+pub use latency::Latency;
+pub use rate_limit::{Bucket, RateLimiter};
+pub use series::{DailySeries, Event};
+pub use source::{FixtureSource, GithubSource, LatestRelease, Recorder, RestSource, Run};
+pub use window::Window;
+
+use labels::{categorise, LabelStats};
+use latency::Resolutions;
+use reviews::ReviewActivity;
+use source::{ListQueries, ListQuery};
+use window::Span;
 
 static CREATED_STRING: &str = "+created%3A";
 static MERGED_STRING: &str = "+merged%3A";
 static CLOSED_STRING: &str = "+closed%3A";
-static ISSUE_URL: &str = "https://github.com/rh-hideout/pokeemerald-expansion/issues";
-static PRS_URL: &str = "https://github.com/rh-hideout/pokeemerald-expansion/pulls";
-static PR_URL: &str = "https://github.com/rh-hideout/pokeemerald-expansion/pull";
-static PR_OPENED: &str = "https://github.com/rh-hideout/pokeemerald-expansion/pulls?q=is%3Apr+sort%3Aupdated-asc";
-static PR_MERGED: &str = "https://github.com/rh-hideout/pokeemerald-expansion/pulls?q=is%3Apr+is%3Amerged+sort%3Aupdated-asc+draft%3Afalse";
-static ISSUE_OPENED: &str = "https://github.com/rh-hideout/pokeemerald-expansion/issues?q=is%253Aissue+sort%3Aupdated-asc";
-static ISSUE_CLOSED: &str = "https://github.com/rh-hideout/pokeemerald-expansion/issues?q=is%253Aissue+is%253Aclosed+sort%3Aupdated-asc";
+
+/// Searches of the open pull requests out of draft, by review decision.
+static READY_QUERY: &str = "is%3Apr+is%3Aopen+draft%3Afalse+-review%3Aapproved+-review%3Achanges_requested";
+static APPROVED_QUERY: &str = "is%3Apr+is%3Aopen+draft%3Afalse+review%3Aapproved";
+static CHANGES_REQUESTED_QUERY: &str = "is%3Apr+is%3Aopen+draft%3Afalse+review%3Achanges_requested";
+
+/// Reviewers shown in the review counts, the most active first.
+static MAX_REVIEWERS: usize = 10;
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Repository {
+    pub owner: String,
+    pub name: String,
+}
+
+impl Repository {
+    pub fn new(owner: &str, name: &str) -> Self {
+        Self { owner: owner.to_string(), name: name.to_string() }
+    }
+
+    pub fn url(&self) -> String {
+        format!("https://github.com/{}/{}", self.owner, self.name)
+    }
+
+    fn pull_url(&self, number: u64) -> String {
+        format!("{}/pull/{number}", self.url())
+    }
+}
+
+impl TryFrom<String> for Repository {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Repository> for String {
+    fn from(repository: Repository) -> Self {
+        repository.to_string()
+    }
+}
+
+impl fmt::Display for Repository {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.owner, self.name)
+    }
+}
+
+impl FromStr for Repository {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().split_once('/') {
+            Some((owner, name)) if !owner.is_empty() && !name.is_empty() && !name.contains('/') => Ok(Self::new(owner, name)),
+            _ => Err(format!("invalid repository \"{s}\", expected \"owner/name\"")),
+        }
+    }
+}
 
 #[derive(Clone, Debug)]
-struct ParsedIssue {
-    user: String,
-    id: u64,
-    title: String,
-    state: IssueState,
-    creation_date: DateTime<Utc>,
-    updated_date: DateTime<Utc>,
-    closed_date: Option<DateTime<Utc>>,
-    labels: Vec<Label>
+pub struct ParsedIssue {
+    pub user: String,
+    pub id: u64,
+    pub url: String,
+    pub title: String,
+    pub state: IssueState,
+    pub creation_date: DateTime<Utc>,
+    pub updated_date: DateTime<Utc>,
+    pub closed_date: Option<DateTime<Utc>>,
+    pub labels: Vec<String>
 }
 
 impl ParsedIssue {
-    fn list_render(&self, is_pr: bool) -> String {
-        format!("* [#{iu} - {it}]({url}/{iu}) | {id}\n",
-            it=self.title, url=if is_pr {ISSUE_URL} else {PR_URL}, iu=self.id,
-            id=format_timestamp_since(self.updated_date.timestamp().unsigned_abs()))
+    fn open_at(&self, at: DateTime<Utc>) -> bool {
+        self.creation_date <= at && (self.state == IssueState::Open || self.closed_date.is_some_and(|d| d > at))
+    }
+
+    fn list_render(&self, now: DateTime<Utc>, timestamps: Timestamps) -> String {
+        format!("* [#{iu} - {it}]({url}) | {id}\n",
+            it=self.title, url=self.url, iu=self.id,
+            id=format_timestamp_since(self.updated_date, now, timestamps))
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
-enum PRState {
-    Open,
+/// Where a pull request is in its lifecycle, see `pr_state`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum PRState {
+    /// Open as a draft.
     Draft,
+    /// Open, ready for review and without a review decision.
+    #[default]
+    Ready,
+    /// Open and approved, without changes requested.
+    Approved,
+    /// Open, with the latest verdict of a reviewer requesting changes.
+    ChangesRequested,
     Merged,
-    Cancelled
+    /// Closed without being merged.
+    Closed,
 }
-impl Default for PRState {
-    fn default() -> Self {
-        Self::Open
+
+impl PRState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Draft => "draft",
+            Self::Ready => "ready",
+            Self::Approved => "approved",
+            Self::ChangesRequested => "changes_requested",
+            Self::Merged => "merged",
+            Self::Closed => "closed",
+        }
+    }
+
+    /// Open and out of draft, whatever the reviews decided.
+    fn is_ready(&self) -> bool {
+        matches!(self, Self::Ready | Self::Approved | Self::ChangesRequested)
     }
 }
 
-#[derive(Debug)]
-struct ParsedPR {
-    user: String,
-    id: u64,
-    title: String,
-    state: PRState,
-    open_state: IssueState,
-    creation_date: DateTime<Utc>,
-    updated_date: DateTime<Utc>,
-    closed_date: Option<DateTime<Utc>>,
-    labels: Vec<Label>
+impl FromStr for PRState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "draft" => Ok(Self::Draft),
+            "ready" => Ok(Self::Ready),
+            "approved" => Ok(Self::Approved),
+            "changes_requested" => Ok(Self::ChangesRequested),
+            "merged" => Ok(Self::Merged),
+            "closed" => Ok(Self::Closed),
+            _ => Err(format!("unknown pull request state \"{s}\"")),
+        }
+    }
+}
+
+/// Verdict of a submitted review.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ReviewState {
+    Approved,
+    ChangesRequested,
+    Commented,
+    Dismissed,
+}
+
+impl ReviewState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Approved => "approved",
+            Self::ChangesRequested => "changes_requested",
+            Self::Commented => "commented",
+            Self::Dismissed => "dismissed",
+        }
+    }
+}
+
+impl FromStr for ReviewState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "approved" => Ok(Self::Approved),
+            "changes_requested" => Ok(Self::ChangesRequested),
+            "commented" => Ok(Self::Commented),
+            "dismissed" => Ok(Self::Dismissed),
+            _ => Err(format!("unknown review state \"{s}\"")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedReview {
+    pub reviewer: String,
+    pub state: ReviewState,
+    pub submitted_date: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ParsedPR {
+    pub user: String,
+    pub id: u64,
+    pub url: String,
+    pub title: String,
+    pub state: PRState,
+    pub open_state: IssueState,
+    pub creation_date: DateTime<Utc>,
+    pub updated_date: DateTime<Utc>,
+    pub closed_date: Option<DateTime<Utc>>,
+    pub labels: Vec<String>,
+    /// Users and teams, as `org/team`, whose review is still awaited.
+    pub requested_reviewers: Vec<String>,
+    /// Submitted reviews, oldest first. Empty for the pull requests of the lists.
+    pub reviews: Vec<ParsedReview>,
 }
 
 impl ParsedPR {
-    fn list_render(&self) -> String {
-        format!("* [#{iu} - {it}]({PR_URL}/{iu}) | {id}\n",
-            it=self.title, iu=self.id,
-            id=format_timestamp_since(self.updated_date.timestamp().unsigned_abs()))
+    fn open_at(&self, at: DateTime<Utc>) -> bool {
+        self.creation_date <= at && (self.open_state == IssueState::Open || self.closed_date.is_some_and(|d| d > at))
+    }
+
+    /// Reviews by someone other than the author, who may answer review comments with reviews of their own.
+    fn peer_reviews(&self) -> impl Iterator<Item = &ParsedReview> {
+        self.reviews.iter().filter(|r| r.reviewer != self.user)
+    }
+
+    fn first_review(&self) -> Option<DateTime<Utc>> {
+        self.peer_reviews().map(|r| r.submitted_date).min()
+    }
+
+    /// Ready for review at `at` without anyone having reviewed it yet.
+    fn waiting_at(&self, at: DateTime<Utc>) -> bool {
+        self.open_at(at) && self.state != PRState::Draft && self.first_review().is_none_or(|reviewed| reviewed > at)
+    }
+
+    /// Sets the reviews, which decide the state of an open pull request ready for review.
+    fn with_reviews(mut self, reviews: Vec<ParsedReview>) -> Self {
+        self.reviews = reviews;
+        if self.state.is_ready() {
+            self.state = self.review_decision(DateTime::<Utc>::MAX_UTC);
+        }
+        self
+    }
+
+    /// State at `at` of a pull request open then. Drafts stay drafts, as only the current draft state is known,
+    /// and the others are judged by the reviews submitted until then.
+    fn state_at(&self, at: DateTime<Utc>) -> PRState {
+        match self.state {
+            PRState::Draft => PRState::Draft,
+            _ => self.review_decision(at),
+        }
+    }
+
+    /// Changes are requested while the latest verdict of any reviewer requests them, otherwise the pull request is
+    /// approved once any reviewer approved it. Comments leave verdicts as they are, and dismissals withdraw them.
+    fn review_decision(&self, at: DateTime<Utc>) -> PRState {
+        let mut verdicts: HashMap<&str, ReviewState> = HashMap::new();
+        for review in self.peer_reviews().filter(|r| r.submitted_date <= at && r.state != ReviewState::Commented) {
+            verdicts.insert(&review.reviewer, review.state);
+        }
+        match (verdicts.values().any(|v| *v == ReviewState::ChangesRequested), verdicts.values().any(|v| *v == ReviewState::Approved)) {
+            (true, _) => PRState::ChangesRequested,
+            (false, true) => PRState::Approved,
+            (false, false) => PRState::Ready,
+        }
+    }
+
+    fn list_render(&self, now: DateTime<Utc>, timestamps: Timestamps) -> String {
+        format!("* [#{iu} - {it}]({url}) | {id}\n",
+            it=self.title, url=self.url, iu=self.id,
+            id=format_timestamp_since(self.updated_date, now, timestamps))
     }
 }
 
 #[derive(Debug, Clone)]
 struct TimedStats {
-    date: Option<NaiveDate>,
+    window: Window,
+    span: Span,
     opened_prs: usize,
     merged_prs: usize,
     cancelled_prs: usize,
 
     opened_issues: usize,
     closed_issues: usize,
+
+    /// How long the pull requests merged and the issues closed within the window took, sorted.
+    merge_times: Vec<TimeDelta>,
+    close_times: Vec<TimeDelta>,
+    /// How long the pull requests first reviewed within the window waited for it, sorted.
+    review_times: Vec<TimeDelta>,
+    /// Reviews each reviewer submitted within the window.
+    reviews: BTreeMap<String, usize>,
 }
 
-impl Default for TimedStats {
-    fn default() -> TimedStats {
-        TimedStats { date: Some(chrono::offset::Utc::now().date_naive()), opened_prs: 0, merged_prs: 0, cancelled_prs: 0, opened_issues: 0, closed_issues: 0 }
+impl AddAssign<&TimedStats> for TimedStats {
+    fn add_assign(&mut self, other: &TimedStats) {
+        // Repositories released on different days widen the span to cover all of them
+        self.span.start = self.span.start.min(other.span.start);
+        self.span.end = self.span.end.max(other.span.end);
+        self.opened_prs += other.opened_prs;
+        self.merged_prs += other.merged_prs;
+        self.cancelled_prs += other.cancelled_prs;
+        self.opened_issues += other.opened_issues;
+        self.closed_issues += other.closed_issues;
+        self.merge_times.extend(&other.merge_times);
+        self.merge_times.sort();
+        self.close_times.extend(&other.close_times);
+        self.close_times.sort();
+        self.review_times.extend(&other.review_times);
+        self.review_times.sort();
+        for (reviewer, count) in &other.reviews {
+            *self.reviews.entry(reviewer.clone()).or_default() += count;
+        }
     }
 }
 
 impl TimedStats {
-    fn since_date(datetime: NaiveDate, issues: &mut [ParsedIssue], pull_requests: &mut [ParsedPR]) -> Self {
+    fn count(window: Window, span: Span, series: &DailySeries, merges: &Resolutions, closes: &Resolutions, reviews: &ReviewActivity, now: DateTime<Utc>) -> Self {
         Self {
-            date: Some(datetime),
-            opened_prs: pull_requests.iter().filter(|p| p.creation_date.date_naive() >= datetime).count(),
-            merged_prs: pull_requests.iter().filter(|p| (p.state==PRState::Merged) && p.closed_date.is_some() && (p.closed_date.unwrap().date_naive() >= datetime)).count(),
-            cancelled_prs: pull_requests.iter().filter(|p| (p.state==PRState::Cancelled) && p.closed_date.is_some() && (p.closed_date.unwrap().date_naive() >= datetime)).count(),
-            opened_issues: issues.iter().filter(|i| i.creation_date.date_naive() >= datetime).count(),
-            closed_issues: issues.iter().filter(|i| i.closed_date.is_some() && (i.closed_date.unwrap().date_naive() >= datetime)).count()
+            window,
+            span,
+            opened_prs: series.count(Event::OpenedPr, span),
+            merged_prs: series.count(Event::MergedPr, span),
+            cancelled_prs: series.count(Event::CancelledPr, span),
+            opened_issues: series.count(Event::OpenedIssue, span),
+            closed_issues: series.count(Event::ClosedIssue, span),
+            merge_times: merges.within(span, now),
+            close_times: closes.within(span, now),
+            review_times: reviews.time_to_first_review(span, now),
+            reviews: reviews.within(span),
         }
     }
 
-    fn on_date(datetime: NaiveDate, issues: &mut [ParsedIssue], pull_requests: &mut [ParsedPR]) -> Self {
-        Self {
-            date: Some(datetime),
-            opened_prs: pull_requests.iter().filter(|p| p.creation_date.date_naive() == datetime).count(),
-            merged_prs: pull_requests.iter().filter(|p| (p.state==PRState::Merged) && p.closed_date.is_some() && (p.closed_date.unwrap().date_naive() == datetime)).count(),
-            cancelled_prs: pull_requests.iter().filter(|p| (p.state==PRState::Cancelled) && p.closed_date.is_some() && (p.closed_date.unwrap().date_naive() == datetime)).count(),
-            opened_issues: issues.iter().filter(|i| i.creation_date.date_naive() == datetime).count(),
-            closed_issues: issues.iter().filter(|i| i.closed_date.is_some() && (i.closed_date.unwrap().date_naive() == datetime)).count()
-        }
+    fn time_to_merge(&self) -> Option<Latency> {
+        Latency::of(&self.merge_times)
     }
-    
-    fn all_time(issues: &mut [ParsedIssue], pull_requests: &mut [ParsedPR]) -> Self {
-        Self {
-            date: None,
-            opened_prs: pull_requests.len(),
-            merged_prs: pull_requests.iter().filter(|p| (p.state==PRState::Merged) && p.closed_date.is_some()).count(),
-            cancelled_prs: pull_requests.iter().filter(|p| (p.state==PRState::Cancelled) && p.closed_date.is_some()).count(),
-            opened_issues: issues.len(),
-            closed_issues: issues.iter().filter(|i| i.closed_date.is_some()).count()
-        }
+
+    fn time_to_close(&self) -> Option<Latency> {
+        Latency::of(&self.close_times)
+    }
+
+    fn time_to_first_review(&self) -> Option<Latency> {
+        Latency::of(&self.review_times)
     }
 }
 
-#[derive(Default, Debug)]
+#[derive(Clone, Copy, Debug)]
+enum Metric {
+    OpenedPrs,
+    MergedPrs,
+    OpenedIssues,
+    ClosedIssues,
+}
+
+#[derive(Clone, Default, Debug)]
 pub struct GithubData {
+    config: Arc<Config>,
+    repositories: Vec<Repository>,
     date: DateTime<Utc>,
     open_issues: usize,
-    confirmed_issues: usize,
-    unconfirmed_issues: usize,
-    feature_requests: usize,
+    /// Open issues of each configured category, in order.
+    category_issues: Vec<usize>,
+    /// Open issues in none of the categories, oldest first.
+    uncategorised_issues: Vec<ParsedIssue>,
 
     open_pull_requests: usize,
     ready_pull_requests: usize,
+    approved_pull_requests: usize,
+    changes_requested_pull_requests: usize,
     draft_pull_requests: usize,
 
     stale_issues: Vec<ParsedIssue>,
@@ -140,281 +396,981 @@ pub struct GithubData {
     most_recent_issues: Vec<ParsedIssue>,
     most_recent_pull_requests: Vec<ParsedPR>,
 
-    yesterday: TimedStats,
-    last_week: TimedStats,
-    last_month: TimedStats,
-    last_year: TimedStats,
-    all: TimedStats,
+    /// Ready pull requests nobody but their author has reviewed, oldest first.
+    waiting_pull_requests: Vec<ParsedPR>,
+    /// Review requests of each reviewer on the open pull requests.
+    review_requests: BTreeMap<String, usize>,
+
+    /// Stats of each configured window, in order.
+    stats: Vec<TimedStats>,
+    /// Stats of each label of the breakdown.
+    label_stats: Vec<LabelStats>,
+
+    warnings: Vec<String>,
 }
 
 impl GithubData {
-    pub fn new() -> Self {
-        Self::default()
+    pub fn new(repository: Repository, config: Arc<Config>) -> Self {
+        Self { config, repositories: vec![repository], ..Self::default() }
     }
 
-    pub async fn fetch(&mut self) {
-        self.date = chrono::offset::Utc::now();
+    pub fn aggregate(reports: &[GithubData]) -> Self {
+        let mut data = Self::default();
+        for report in reports {
+            data.repositories.extend(report.repositories.iter().cloned());
+            data.date = data.date.max(report.date);
+            data.open_issues += report.open_issues;
+            match data.category_issues.is_empty() {
+                true => data.category_issues = report.category_issues.clone(),
+                false => data.category_issues.iter_mut().zip(&report.category_issues).for_each(|(count, other)| *count += other),
+            }
+            data.uncategorised_issues.extend(report.uncategorised_issues.iter().cloned());
+            data.open_pull_requests += report.open_pull_requests;
+            data.ready_pull_requests += report.ready_pull_requests;
+            data.approved_pull_requests += report.approved_pull_requests;
+            data.changes_requested_pull_requests += report.changes_requested_pull_requests;
+            data.draft_pull_requests += report.draft_pull_requests;
+            data.stale_issues.extend(report.stale_issues.iter().cloned());
+            data.stale_pull_requests.extend(report.stale_pull_requests.iter().cloned());
+            data.most_recent_issues.extend(report.most_recent_issues.iter().cloned());
+            data.most_recent_pull_requests.extend(report.most_recent_pull_requests.iter().cloned());
+            data.waiting_pull_requests.extend(report.waiting_pull_requests.iter().cloned());
+            for (reviewer, count) in &report.review_requests {
+                *data.review_requests.entry(reviewer.clone()).or_default() += count;
+            }
+            match data.stats.is_empty() {
+                true => data.stats = report.stats.clone(),
+                false => data.stats.iter_mut().zip(&report.stats).for_each(|(stats, other)| *stats += other),
+            }
+            for other in &report.label_stats {
+                match data.label_stats.iter_mut().find(|s| s.label == other.label) {
+                    Some(stats) => *stats += other,
+                    None => data.label_stats.push(other.clone()),
+                }
+            }
+            data.warnings.extend(report.warnings.iter().cloned());
+        }
+        if let Some(first) = reports.first() {
+            data.config = Arc::clone(&first.config);
+        }
 
-        let _today = self.date.date_naive();
-        let yesterday = self.date.date_naive().pred_opt().unwrap();
-        let mut last_7_days = yesterday;
-        for _ in 0..7 {last_7_days = last_7_days.pred_opt().unwrap()};
-        let mut last_30_days = yesterday;
-        for _ in 0..30 {last_30_days = last_30_days.pred_opt().unwrap()};
-        let mut last_365_days = yesterday;
-        for _ in 0..365 { last_365_days = last_365_days.pred_opt().unwrap()};
+        let stale = data.config.lists.stale as usize;
+        let recent = data.config.lists.recent as usize;
+        data.stale_issues.sort_by_key(|i| i.updated_date);
+        data.stale_issues.truncate(stale);
+        data.stale_pull_requests.sort_by_key(|p| p.updated_date);
+        data.stale_pull_requests.truncate(stale);
+        data.most_recent_issues.sort_by_key(|i| std::cmp::Reverse(i.creation_date));
+        data.most_recent_issues.truncate(recent);
+        data.most_recent_pull_requests.sort_by_key(|p| std::cmp::Reverse(p.creation_date));
+        data.most_recent_pull_requests.truncate(recent);
+        data.waiting_pull_requests.sort_by_key(|p| p.creation_date);
+        data.uncategorised_issues.sort_by_key(|i| i.creation_date);
+        data
+    }
 
-        let mut issues: Vec<ParsedIssue> = Vec::new();
-        let mut pull_requests: Vec<ParsedPR> = Vec::new();
+    pub fn date(&self) -> DateTime<Utc> {
+        self.date
+    }
+
+    pub fn repositories(&self) -> &[Repository] {
+        &self.repositories
+    }
+
+    pub fn scope(&self) -> String {
+        self.repositories.iter().map(|r| r.to_string()).collect::<Vec<String>>().join(",")
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            scope: self.scope(),
+            taken_at: self.date,
+            open_issues: self.open_issues,
+            categories: self.categories().map(|(category, count)| (category.name.clone(), count)).collect(),
+            uncategorised_issues: self.uncategorised_issues.len(),
+            open_pull_requests: self.open_pull_requests,
+            // Snapshots predate reviews, so they count every open pull request out of draft as ready
+            ready_pull_requests: self.ready_pull_requests + self.approved_pull_requests + self.changes_requested_pull_requests,
+            draft_pull_requests: self.draft_pull_requests,
+        }
+    }
+
+    /// Repositories covered by the report, in plain text.
+    pub fn name(&self) -> String {
+        self.repositories.iter().map(|r| r.to_string()).collect::<Vec<String>>().join(" + ")
+    }
 
+    pub fn title(&self) -> String {
+        self.repositories.iter()
+            .map(|r| format!("[{r}]({})", r.url()))
+            .collect::<Vec<String>>()
+            .join(" + ")
+    }
+
+    fn search(&self, query: &str) -> String {
+        let repositories = self.repositories.iter().map(|r| format!("repo:{r}")).collect::<Vec<String>>().join(" ");
+        format!("{repositories} {query}")
+    }
+
+    fn issues_search_url(&self) -> String {
+        match self.repositories.as_slice() {
+            [repository] => format!("{}/issues?q=", repository.url()),
+            repositories => format!("https://github.com/search?type=issues&q={}", search_qualifiers(repositories)),
+        }
+    }
+
+    fn pulls_search_url(&self) -> String {
+        match self.repositories.as_slice() {
+            [repository] => format!("{}/pulls?q=", repository.url()),
+            repositories => format!("https://github.com/search?type=pullrequests&q={}", search_qualifiers(repositories)),
+        }
+    }
 
-        let octocrab = octocrab::instance();
-        let mut issues_page = octocrab
-            .issues("rh-hideout", "pokeemerald-expansion")
-            .list()
-            .state(octocrab::params::State::All)
-            .sort(octocrab::params::issues::Sort::Updated)
-            .per_page(100)
-            .send()
-            .await.unwrap();
-        loop {
-            for issue in &issues_page {
-                if issue.pull_request.is_none(){issues.push(parse_issue(issue.clone()))}
+    /// Refreshes the report as of `clock`'s instant. Malformed items are skipped and listed in the warnings, any other failure aborts the fetch.
+    pub async fn fetch(&mut self, database: &Database, source: &dyn GithubSource, clock: &dyn Clock) -> Result<(), FetchError> {
+        let today = clock.now().with_timezone(&self.config.report.timezone).date_naive().to_string();
+        for repository in self.repositories.clone() {
+            // Once a day every issue is fetched again, as the deleted and transferred ones never show up as updated
+            let full_sync = database.meta(&full_sync_key(&repository))?.as_deref() != Some(today.as_str());
+            let since = match full_sync {
+                true => None,
+                false => database.last_sync(&repository, SyncKind::Issues)?,
+            };
+            let updated_issues = source.issues(&repository, since, &mut self.warnings).await?;
+            match full_sync {
+                true => database.replace_issues(&repository, &updated_issues)?,
+                false => database.save_issues(&repository, &updated_issues)?,
             }
-            issues_page = match octocrab
-                .get_page::<models::issues::Issue>(&issues_page.next)
-                .await.unwrap()
-            {
-                Some(next_page) => next_page,
-                None => break,
+            let since = database.last_sync(&repository, SyncKind::PullRequests)?;
+            let reviews_since = database.last_sync(&repository, SyncKind::Reviews)?.max(since);
+            let updated_pull_requests = source.pull_requests(&repository, since, reviews_since, &mut self.warnings).await?;
+            database.save_pull_requests(&repository, &updated_pull_requests)?;
+            println!("{repository}: {} issues and {} pull requests updated since {since:?}", updated_issues.len(), updated_pull_requests.len());
+            if self.config.report.windows.contains(&Window::SinceRelease) {
+                let release = source.latest_release(&repository).await?;
+                database.set_meta(&release_key(&repository), &serde_json::to_string(&release).unwrap())?;
+            }
+            if full_sync {
+                database.set_meta(&full_sync_key(&repository), &today)?;
             }
         }
+        self.compute(database, clock)?;
+
+        let lists = source.lists(&self.list_queries(), &mut self.warnings).await?;
+        self.stale_issues = lists.stale_issues;
+        self.stale_pull_requests = lists.stale_pull_requests;
+        self.most_recent_issues = lists.recent_issues;
+        self.most_recent_pull_requests = lists.recent_pull_requests;
+        Ok(())
+    }
 
-        let mut pull_requests_page = octocrab
-            .pulls("rh-hideout", "pokeemerald-expansion")
-            .list()
-            .state(octocrab::params::State::All)
-            .sort(octocrab::params::pulls::Sort::Updated)
-            .per_page(100)
-            .send()
-            .await.unwrap();
-        loop {
-            for pr in &pull_requests_page {
-                pull_requests.push(parse_pr(pr.clone()));
+    /// Computes the counts and stats as of `clock`'s instant from the cached issues and pull requests, without GitHub.
+    /// Items are counted as open by their creation and closing dates, and pull requests judged by the reviews submitted
+    /// until then, but with their current labels, draft state and review requests.
+    pub fn compute(&mut self, database: &Database, clock: &dyn Clock) -> Result<(), FetchError> {
+        self.date = clock.now();
+
+        let mut issues: Vec<ParsedIssue> = Vec::new();
+        let mut pull_requests: Vec<ParsedPR> = Vec::new();
+        let mut releases = Vec::new();
+        for repository in &self.repositories {
+            issues.extend(database.issues(repository)?);
+            pull_requests.extend(database.pull_requests(repository)?);
+            if self.config.report.windows.contains(&Window::SinceRelease) {
+                match database.meta(&release_key(repository))?.and_then(|release| serde_json::from_str::<LatestRelease>(&release).ok()) {
+                    Some(release) => releases.push(release.published),
+                    None => self.warnings.push(format!("{repository} has no release, \"{}\" counts all time", Window::SinceRelease.label())),
+                }
             }
-            pull_requests_page = match octocrab
-                .get_page::<models::pulls::PullRequest>(&pull_requests_page.next)
-                .await.unwrap()
-            {
-                Some(next_page) => next_page,
-                None => break,
+        }
+
+        let categories = &self.config.labels.categories;
+        self.category_issues = vec![0; categories.len()];
+        self.uncategorised_issues = Vec::new();
+        for issue in issues.iter().filter(|i| i.open_at(self.date)) {
+            match categorise(categories, &issue.labels) {
+                Some(category) => self.category_issues[category] += 1,
+                None => self.uncategorised_issues.push(issue.clone()),
             }
         }
-        
-        for issue in issues.iter().filter(|i| i.state==IssueState::Open) {
-            if issue.labels.iter().any(|l| l.name == "status: unconfirmed") {self.unconfirmed_issues += 1;}
-            else if issue.labels.iter().any(|l| l.name == "status: confirmed") {self.confirmed_issues += 1;}
-            else if issue.labels.iter().any(|l| l.name == "feature-request") {self.feature_requests += 1;}
-        }
-        self.open_issues = self.confirmed_issues+self.unconfirmed_issues+self.feature_requests;
-        
-        self.draft_pull_requests = pull_requests.iter().filter(|p| (p.open_state==IssueState::Open) & (p.state==PRState::Draft)).count();
-        self.ready_pull_requests = pull_requests.iter().filter(|p| p.state==PRState::Open).count();
-        self.open_pull_requests = self.draft_pull_requests + self.ready_pull_requests;
-
-        self.yesterday = TimedStats::on_date(yesterday, &mut issues, &mut pull_requests);
-        self.last_week = TimedStats::since_date(last_7_days, &mut issues, &mut pull_requests);
-        self.last_month = TimedStats::since_date(last_30_days, &mut issues, &mut pull_requests);
-        self.last_year = TimedStats::since_date(last_365_days, &mut issues, &mut pull_requests);
-        self.all = TimedStats::all_time(&mut issues, &mut pull_requests);
-
-        let stale_issues = octocrab
-            .search()
-            .issues_and_pull_requests(&"repo:rh-hideout/pokeemerald-expansion is:open sort:updated-asc label:bug is:issue")
-            .per_page(3)
-            .send().await.unwrap();
-        for issue in &stale_issues {
-            self.stale_issues.push(parse_issue(issue.clone()));
-        }
-        let stale_pull_requests = octocrab
-            .search().issues_and_pull_requests(&"repo:rh-hideout/pokeemerald-expansion is:open sort:updated-asc draft:false is:pr")
-            .per_page(3)
-            .send().await.unwrap();
-        for pr in &stale_pull_requests {
-            self.stale_pull_requests.push(parse_pr_from_issue(pr.clone()));
-        }
-
-        let most_recent_issues = octocrab
-            .search().issues_and_pull_requests(&"repo:rh-hideout/pokeemerald-expansion is:open sort:created-desc is:issue")
-            .per_page(3)
-            .send().await.unwrap();
-        for issue in most_recent_issues {
-            self.most_recent_issues.push(parse_issue(issue));
-        }
-        let most_recent_pull_requests = octocrab
-            .search().issues_and_pull_requests(&"repo:rh-hideout/pokeemerald-expansion is:open sort:created-desc is:pr")
-            .per_page(3)
-            .send().await.unwrap();
-        for pr in most_recent_pull_requests {
-            self.most_recent_pull_requests.push(parse_pr_from_issue(pr));
-        }
-
-        let test = octocrab.ratelimit().get().await.unwrap();
-
-        println!("Rate limit: {:#?}\n{:#?}", test.resources.core, test.resources.search);
-        println!("Resets in {:#?} minutes", (test.resources.core.reset - SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs())/60);
+        self.uncategorised_issues.sort_by_key(|i| i.creation_date);
+        self.open_issues = self.category_issues.iter().sum::<usize>() + self.uncategorised_issues.len();
+
+        let open_states: Vec<PRState> = pull_requests.iter().filter(|p| p.open_at(self.date)).map(|p| p.state_at(self.date)).collect();
+        let open = |state: PRState| open_states.iter().filter(|s| **s == state).count();
+        self.draft_pull_requests = open(PRState::Draft);
+        self.ready_pull_requests = open(PRState::Ready);
+        self.approved_pull_requests = open(PRState::Approved);
+        self.changes_requested_pull_requests = open(PRState::ChangesRequested);
+        self.open_pull_requests = open_states.len();
+
+        self.waiting_pull_requests = pull_requests.iter().filter(|p| p.waiting_at(self.date)).cloned().collect();
+        self.waiting_pull_requests.sort_by_key(|p| p.creation_date);
+        self.review_requests = BTreeMap::new();
+        for reviewer in pull_requests.iter().filter(|p| p.open_at(self.date)).flat_map(|p| &p.requested_reviewers) {
+            *self.review_requests.entry(reviewer.clone()).or_default() += 1;
+        }
+
+        let release = releases.into_iter().min();
+        let series = DailySeries::new(&issues, &pull_requests, self.timezone(), self.date);
+        let merges = Resolutions::new(pull_requests.iter()
+            .filter(|p| p.state == PRState::Merged)
+            .filter_map(|p| Some((p.creation_date, p.closed_date?))));
+        let closes = Resolutions::new(issues.iter().filter_map(|i| Some((i.creation_date, i.closed_date?))));
+        let reviews = ReviewActivity::new(&pull_requests, self.date);
+        self.stats = self.config.report.windows.iter()
+            .map(|window| TimedStats::count(*window, window.span(self.date, self.timezone(), release), &series, &merges, &closes, &reviews, self.date))
+            .collect();
+        let spans: Vec<Span> = self.stats.iter().map(|s| s.span).collect();
+        self.label_stats = LabelStats::count(&issues, &self.config.labels.breakdown, &spans, self.date);
+        Ok(())
+    }
+
+    fn list_queries(&self) -> ListQueries {
+        let (stale, recent) = (self.config.lists.stale, self.config.lists.recent);
+        ListQueries {
+            stale_issues: ListQuery { query: self.search(&format!("is:open sort:updated-asc label:\"{}\" is:issue", self.config.labels.bug)), count: stale },
+            stale_pull_requests: ListQuery { query: self.search("is:open sort:updated-asc draft:false is:pr"), count: stale },
+            recent_issues: ListQuery { query: self.search("is:open sort:created-desc is:issue"), count: recent },
+            recent_pull_requests: ListQuery { query: self.search("is:open sort:created-desc is:pr"), count: recent },
+        }
+    }
+
+    /// Problems met while building the report, shown along with it.
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    pub fn warn(&mut self, warning: String) {
+        self.warnings.push(warning);
+    }
+
+    /// Windows the stats are counted over.
+    pub fn windows(&self) -> &[Window] {
+        &self.config.report.windows
+    }
+
+    /// Timezone deciding where the days of the stats windows start.
+    pub fn timezone(&self) -> Tz {
+        self.config.report.timezone
+    }
+
+    /// How the lists show update dates in Discord.
+    pub fn timestamps(&self) -> Timestamps {
+        match self.config.discord.timestamps {
+            true => Timestamps::Discord,
+            false => Timestamps::Relative,
+        }
     }
 
+    /// Renders the report for Discord.
     pub fn render(&self) -> String {
-        println!("{:#?}", self);
+        self.render_with(self.timestamps())
+    }
+
+    /// Renders the report for places other than Discord, which do not understand its timestamp markup.
+    pub fn render_plain(&self) -> String {
+        self.render_with(Timestamps::Relative)
+    }
+
+    fn render_with(&self, timestamps: Timestamps) -> String {
+
+        let mut md = format!("# {}\n\n", self.title());
+        for warning in &self.warnings {
+            md.push_str(&format!("> ⚠️ {warning}\n"));
+        }
+        md.push_str(&self.render_raw_stats());
+        md.push_str(&self.render_stales(timestamps));
+        md.push_str(&self.render_triage(timestamps));
+        md.push_str(&self.render_last_created(timestamps));
+        md.push_str(&self.render_stats());
+        md.push_str(&self.render_latency());
+        md.push_str(&self.render_reviews(timestamps));
+        md.push_str(&self.render_labels());
+        md
+    }
+
+    fn render_raw_stats(&self) -> String {
+        let issues = self.issues_search_url();
+        let prs = self.pulls_search_url();
+        let categories = self.categories()
+            .map(|(category, count)| match self.category_url(category) {
+                Some(url) => format!("[{count} {}]({url}) / ", category.name),
+                None => format!("{count} {} / ", category.name),
+            })
+            .collect::<String>();
 
-        // Raw Stats
         let mut md = String::from("# Raw Stats (Currently Open)\n\n");
-        md.push_str(&format!("* [{} Issues]({ISSUE_URL}) ([{} Confirmed Bugs]({ISSUE_URL}?q=is%3Aissue+is%3Aopen+label%3A\"status%3A+confirmed\") / [{} Unconfirmed Bugs]({ISSUE_URL}?q=is%3Aissue+is%3Aopen+label%3A\"status%3A+unconfirmed\") / [{} Feature Requests]({ISSUE_URL}?q=is%3Aissue+is%3Aopen+label%3Afeature-request))\n", self.open_issues, self.confirmed_issues, self.unconfirmed_issues, self.feature_requests));
-        md.push_str(&format!("* [{} Pull Requests]({PRS_URL}?q=is%3Apr+is%3Aopen) ([{} Ready for Review]({PRS_URL}?q=is%3Apr+is%3Aopen+draft%3Afalse) / [{} Draft]({PRS_URL}?q=is%3Apr+is%3Aopen+draft%3Atrue))\n", self.open_pull_requests, self.ready_pull_requests, self.draft_pull_requests));
+        md.push_str(&format!("* [{} Issues]({issues}is%3Aissue+is%3Aopen) ({categories}{} Uncategorised)\n", self.open_issues, self.uncategorised_issues.len()));
+        md.push_str(&format!("* [{} Pull Requests]({prs}is%3Apr+is%3Aopen) ([{} Ready for Review]({prs}{READY_QUERY}) / [{} Approved]({prs}{APPROVED_QUERY}) / [{} Changes Requested]({prs}{CHANGES_REQUESTED_QUERY}) / [{} Draft]({prs}is%3Apr+is%3Aopen+draft%3Atrue))\n",
+            self.open_pull_requests, self.ready_pull_requests, self.approved_pull_requests, self.changes_requested_pull_requests, self.draft_pull_requests));
+        md
+    }
 
-        // Stales
-        md.push_str("# Stales\n\n### [Pull Requests](https://github.com/rh-hideout/pokeemerald-expansion/pulls?q=is%3Apr+is%3Aopen+draft%3Afalse+sort%3Aupdated-asc)\n");
-        for stale_pr in self.stale_pull_requests.iter() {
-            md.push_str(&stale_pr.list_render());
+    fn render_stales(&self, timestamps: Timestamps) -> String {
+        let mut md = String::from("# Stales\n\n");
+        md.push_str(&self.render_stale_pull_requests(self.stale_pull_requests.len(), timestamps));
+        md.push_str(&self.render_stale_issues(self.stale_issues.len(), timestamps));
+        md
+    }
+
+    pub fn render_stale_pull_requests(&self, count: usize, timestamps: Timestamps) -> String {
+        let mut md = format!("### [Pull Requests]({}is%3Apr+is%3Aopen+draft%3Afalse+sort%3Aupdated-asc)\n", self.pulls_search_url());
+        for stale_pr in self.stale_pull_requests.iter().take(count) {
+            md.push_str(&stale_pr.list_render(self.date, timestamps));
+        }
+        md
+    }
+
+    pub fn render_stale_issues(&self, count: usize, timestamps: Timestamps) -> String {
+        let mut md = format!("### [Bugs]({}is%3Aissue+is%3Aopen+sort%3Aupdated-asc+label%3A{})\n",
+            self.issues_search_url(), encode_query(&format!("\"{}\"", self.config.labels.bug)));
+        for stale_issue in self.stale_issues.iter().take(count) {
+            md.push_str(&stale_issue.list_render(self.date, timestamps));
         }
-        md.push_str("### [Bugs](https://github.com/rh-hideout/pokeemerald-expansion/issues?q=is%3Aopen+sort%3Aupdated-asc)\n");
-        for stale_issue in self.stale_issues.iter() {
-            md.push_str(&stale_issue.list_render(false));
+        md
+    }
+
+    fn render_triage(&self, timestamps: Timestamps) -> String {
+        let mut md = String::from("# Triage\n\n");
+        md.push_str(&self.render_uncategorised_issues(self.config.lists.stale as usize, timestamps));
+        md
+    }
+
+    /// The oldest `count` of the open issues in none of the categories, which still need triaging.
+    pub fn render_uncategorised_issues(&self, count: usize, timestamps: Timestamps) -> String {
+        let mut md = format!("### {} Uncategorised Issues\n", self.uncategorised_issues.len());
+        for issue in self.uncategorised_issues.iter().take(count) {
+            md.push_str(&issue.list_render(self.date, timestamps));
         }
+        md
+    }
 
-        // Last Created
-        md.push_str(&format!("# Last Created\n\n### [Pull Requests]({PRS_URL}?q=is%3Apr+is%3Aopen+sort%3Acreated-desc)\n"));
+    /// Configured categories along with their open issues.
+    fn categories(&self) -> impl Iterator<Item = (&Category, usize)> {
+        self.config.labels.categories.iter().zip(self.category_issues.iter().copied())
+    }
+
+    /// Search link to the open issues of `category`, `None` if GitHub cannot search it.
+    fn category_url(&self, category: &Category) -> Option<String> {
+        Some(format!("{}is%3Aissue+is%3Aopen+{}", self.issues_search_url(), encode_query(&category.query()?)))
+    }
+
+    fn render_last_created(&self, timestamps: Timestamps) -> String {
+        let mut md = String::from("# Last Created\n\n");
+        md.push_str(&self.render_recent_pull_requests(timestamps));
+        md.push_str(&self.render_recent_issues(timestamps));
+        md
+    }
+
+    pub fn render_recent_pull_requests(&self, timestamps: Timestamps) -> String {
+        let mut md = format!("### [Pull Requests]({}is%3Apr+is%3Aopen+sort%3Acreated-desc)\n", self.pulls_search_url());
         for recent_pr in self.most_recent_pull_requests.iter() {
-            md.push_str(&recent_pr.list_render());
+            md.push_str(&recent_pr.list_render(self.date, timestamps));
         }
-        md.push_str(&format!("### [Issues]({ISSUE_URL}?q=is%3Aissue+is%3Aopen+sort%3Acreated-desc))\n"));
+        md
+    }
+
+    pub fn render_recent_issues(&self, timestamps: Timestamps) -> String {
+        let mut md = format!("### [Issues]({}is%3Aissue+is%3Aopen+sort%3Acreated-desc))\n", self.issues_search_url());
         for recent_issue in self.most_recent_issues.iter() {
-            md.push_str(&recent_issue.list_render(false));
-        }
-        
-        // Parsed Stats
-        md.push_str("# Stats\n\nAll stats are displayed as:\n\n**Metric**: yesterday | last 7 days | last 30 days | last 365 days | all time.\n\nRate is \"For every X created, how many are completed?\". For example, 2 means \"For every bug that came in this month, we solved two of them\".\n\nGrowth is how many more of these occured in this time period. For example, -14 means \"This week we merged/closed 14 PRs\".\n\n");
-
-        let yesterday_date_span = format!("{}", self.yesterday.date.unwrap());
-        let last_7_days_date_span = format!("{l7}..{y}", y=self.yesterday.date.unwrap(), l7=self.last_week.date.unwrap());
-        let last_30_days_date_span = format!("{l30}..{y}", y=self.yesterday.date.unwrap(), l30=self.last_month.date.unwrap());
-        let last_365_days_date_span = format!("{l365}..{y}", y=self.yesterday.date.unwrap(), l365=self.last_year.date.unwrap());
-
-        md.push_str(&format!(
-                "## Pull Requests\n\n**Opened PRs**: [{py}]({PR_OPENED}{CREATED_STRING}{yesterday_date_span}) | [{p7}]({PR_OPENED}{CREATED_STRING}{last_7_days_date_span}) | [{p30}]({PR_OPENED}{CREATED_STRING}{last_30_days_date_span}) | [{p365}]({PR_OPENED}{CREATED_STRING}{last_365_days_date_span}) | [{pa}]({PR_OPENED})\n\n",
-            py=self.yesterday.opened_prs,
-            p7=self.last_week.opened_prs,
-            p30=self.last_month.opened_prs,
-            p365=self.last_year.opened_prs,
-            pa=self.all.opened_prs
-        ));
-        md.push_str(&format!(
-                "**Merged PRs**: [{py}]({PR_MERGED}{MERGED_STRING}{yesterday_date_span}) | [{p7}]({PR_MERGED}{MERGED_STRING}{last_7_days_date_span}) | [{p30}]({PR_MERGED}{MERGED_STRING}{last_30_days_date_span}) | [{p365}]({PR_MERGED}{MERGED_STRING}{last_365_days_date_span}) | [{pa}]({PR_MERGED})\n\n",
-            py=self.yesterday.merged_prs,
-            p7=self.last_week.merged_prs,
-            p30=self.last_month.merged_prs,
-            p365=self.last_year.merged_prs,
-            pa=self.all.merged_prs
-        ));
-
-        md.push_str(&format!("**Merge Rate**: {:.2} | {:.2} | {:.2} | {:.2} | {:.2}\n\n",
-                (self.yesterday.merged_prs as f64)/(self.yesterday.opened_prs as f64),
-                (self.last_week.merged_prs as f64)/(self.last_week.opened_prs as f64),
-                (self.last_month.merged_prs as f64)/(self.last_month.opened_prs as f64),
-                (self.last_year.merged_prs as f64)/(self.last_year.opened_prs as f64),
-                (self.all.merged_prs as f64)/(self.all.opened_prs as f64),
-                ));
-        md.push_str(&format!("**PR Growth**: {} | {} | {} | {} | {}\n\n",
-                self.yesterday.opened_prs as i64 - self.yesterday.merged_prs as i64,
-                self.last_week.opened_prs as i64 - self.last_week.merged_prs as i64,
-                self.last_month.opened_prs as i64 - self.last_month.merged_prs as i64,
-                self.last_year.opened_prs as i64 - self.last_year.merged_prs as i64,
-                self.all.opened_prs as i64 - self.all.merged_prs as i64,
-                ));
-
-        md.push_str(&format!(
-                "## Issues\n\n**Opened Issues**: [{py}]({ISSUE_OPENED}{CREATED_STRING}{yesterday_date_span}) | [{p7}]({ISSUE_OPENED}{CREATED_STRING}{last_7_days_date_span}) | [{p30}]({ISSUE_OPENED}{CREATED_STRING}{last_30_days_date_span}) | [{p365}]({ISSUE_OPENED}{CREATED_STRING}{last_365_days_date_span}) | [{pa}]({ISSUE_OPENED})\n\n",
-            py=self.yesterday.opened_issues,
-            p7=self.last_week.opened_issues,
-            p30=self.last_month.opened_issues,
-            p365=self.last_year.opened_issues,
-            pa=self.all.opened_issues
-        ));
-        md.push_str(&format!(
-                "**Closed Issues**: [{py}]({ISSUE_CLOSED}{CLOSED_STRING}{yesterday_date_span}) | [{p7}]({ISSUE_CLOSED}{CLOSED_STRING}{last_7_days_date_span}) | [{p30}]({ISSUE_CLOSED}{CLOSED_STRING}{last_30_days_date_span}) | [{p365}]({ISSUE_CLOSED}{CLOSED_STRING}{last_365_days_date_span}) | [{pa}]({ISSUE_CLOSED})\n\n",
-            py=self.yesterday.closed_issues,
-            p7=self.last_week.closed_issues,
-            p30=self.last_month.closed_issues,
-            p365=self.last_year.closed_issues,
-            pa=self.all.closed_issues
-        ));
-
-        md.push_str(&format!("**Resolution Rate**: {:.2} | {:.2} | {:.2} | {:.2} | {:.2}\n\n",
-                (self.yesterday.closed_issues as f64)/(self.yesterday.opened_issues as f64),
-                (self.last_week.closed_issues as f64)/(self.last_week.opened_issues as f64),
-                (self.last_month.closed_issues as f64)/(self.last_month.opened_issues as f64),
-                (self.last_year.closed_issues as f64)/(self.last_year.opened_issues as f64),
-                (self.all.closed_issues as f64)/(self.all.opened_issues as f64),
-            ));
-        md.push_str(&format!("**Issue Growth**: {} | {} | {} | {} | {}\n\n",
-                self.yesterday.opened_issues as i64 - self.yesterday.closed_issues as i64,
-                self.last_week.opened_issues as i64 - self.last_week.closed_issues as i64,
-                self.last_month.opened_issues as i64 - self.last_month.closed_issues as i64,
-                self.last_year.opened_issues as i64 - self.last_year.closed_issues as i64,
-                self.all.opened_issues as i64 - self.all.closed_issues as i64,
-            ));
+            md.push_str(&recent_issue.list_render(self.date, timestamps));
+        }
         md
     }
+
+    fn render_stats(&self) -> String {
+        let windows = self.config.report.windows.as_slice();
+        let labels = windows.iter().map(|w| w.label()).collect::<Vec<String>>().join(" | ");
+        let mut md = format!("# Stats\n\nAll stats are displayed as:\n\n**Metric**: {labels}.\n\nRate is \"For every X created, how many are completed?\". For example, 2 means \"For every bug that came in this month, we solved two of them\".\n\nGrowth is how many more of these occured in this time period. For example, -14 means \"This week we merged/closed 14 PRs\".\n\n");
+        md.push_str(&self.render_metrics(windows, " | "));
+        md
+    }
+
+    /// Stats of a single window, as answered by the `/stats` command.
+    pub fn render_window(&self, window: Window) -> String {
+        let mut md = match self.date_span(window) {
+            Some(span) => format!("# {} stats for {} ({span})\n\n", self.title(), window.label()),
+            None => format!("# {} stats for {}\n\n", self.title(), window.label()),
+        };
+        md.push_str(&self.render_metrics(&[window], ""));
+        md
+    }
+
+    fn render_latency(&self) -> String {
+        let windows = self.config.report.windows.as_slice();
+        let labels = windows.iter().map(|w| w.label()).collect::<Vec<String>>().join(" | ");
+        let mut md = format!("# Latency\n\nTime from opening to the first review and to merging or closing, of the pull requests first reviewed or merged and the issues closed in each window, displayed as:\n\n**Metric**: {labels}.\n\n");
+        md.push_str(&self.render_latencies(windows, " | "));
+        md
+    }
+
+    /// Latency of a single window, as answered by the `/latency` command.
+    pub fn render_window_latency(&self, window: Window) -> String {
+        let mut md = match self.date_span(window) {
+            Some(span) => format!("# {} latency for {} ({span})\n\n", self.title(), window.label()),
+            None => format!("# {} latency for {}\n\n", self.title(), window.label()),
+        };
+        md.push_str(&self.render_latencies(&[window], ""));
+        md
+    }
+
+    fn render_latencies(&self, windows: &[Window], separator: &str) -> String {
+        let mut md = String::new();
+        let latencies = [
+            ("Time to First Review", TimedStats::time_to_first_review as fn(&TimedStats) -> Option<Latency>),
+            ("Time to Merge", TimedStats::time_to_merge),
+            ("Time to Close", TimedStats::time_to_close),
+        ];
+        for (title, latency) in latencies {
+            let row = |f: &dyn Fn(&Latency) -> String| windows.iter()
+                .filter_map(|w| self.stats(*w))
+                .map(|s| latency(s).map_or(String::from("-"), |l| f(&l)))
+                .collect::<Vec<String>>().join(separator);
+            md.push_str(&format!("## {title}\n\n"));
+            md.push_str(&format!("**Median**: {}\n\n", row(&|l| format_duration(l.median))));
+            md.push_str(&format!("**Mean**: {}\n\n", row(&|l| format_duration(l.mean))));
+            md.push_str(&format!("**75th Percentile**: {}\n\n", row(&|l| format_duration(l.p75))));
+            md.push_str(&format!("**90th Percentile**: {}\n\n", row(&|l| format_duration(l.p90))));
+            md.push_str(&format!("**Count**: {}\n\n", row(&|l| l.count.to_string())));
+        }
+        md
+    }
+
+    fn render_reviews(&self, timestamps: Timestamps) -> String {
+        let windows = self.config.report.windows.as_slice();
+        let labels = windows.iter().map(|w| w.label()).collect::<Vec<String>>().join(" | ");
+        let mut md = String::from("# Reviews\n\n");
+        md.push_str(&self.render_waiting_pull_requests(self.config.lists.stale as usize, timestamps));
+        md.push_str(&self.render_review_requests());
+        md.push_str(&format!("### Reviews per Reviewer\n\nReviews submitted on the pull requests of others, displayed as:\n\n**Reviewer**: {labels}.\n\n"));
+        md.push_str(&self.render_reviewers(windows, " | "));
+        md
+    }
+
+    /// Reviews of a single window, as answered by the `/reviews` command.
+    pub fn render_window_reviews(&self, window: Window, timestamps: Timestamps) -> String {
+        let mut md = match self.date_span(window) {
+            Some(span) => format!("# {} reviews for {} ({span})\n\n", self.title(), window.label()),
+            None => format!("# {} reviews for {}\n\n", self.title(), window.label()),
+        };
+        md.push_str(&self.render_waiting_pull_requests(self.config.lists.stale as usize, timestamps));
+        md.push_str(&self.render_review_requests());
+        md.push_str("### Reviews per Reviewer\n\n");
+        md.push_str(&self.render_reviewers(&[window], ""));
+        md
+    }
+
+    /// The oldest `count` of the ready pull requests nobody has reviewed yet.
+    pub fn render_waiting_pull_requests(&self, count: usize, timestamps: Timestamps) -> String {
+        let mut md = format!("### [{} Waiting on Review]({}is%3Apr+is%3Aopen+draft%3Afalse+review%3Anone+sort%3Acreated-asc)\n",
+            self.waiting_pull_requests.len(), self.pulls_search_url());
+        for waiting_pr in self.waiting_pull_requests.iter().take(count) {
+            md.push_str(&waiting_pr.list_render(self.date, timestamps));
+        }
+        md
+    }
+
+    fn render_review_requests(&self) -> String {
+        let mut md = String::from("### Open Review Requests\n");
+        for (reviewer, count) in self.review_requests() {
+            md.push_str(&format!("* **{reviewer}**: {count}\n"));
+        }
+        md
+    }
+
+    fn render_reviewers(&self, windows: &[Window], separator: &str) -> String {
+        self.reviewers(windows).iter()
+            .map(|reviewer| {
+                let counts = windows.iter()
+                    .filter_map(|w| self.stats(*w))
+                    .map(|s| s.reviews.get(reviewer).copied().unwrap_or_default().to_string())
+                    .collect::<Vec<String>>().join(separator);
+                format!("**{reviewer}**: {counts}\n\n")
+            })
+            .collect()
+    }
+
+    /// Reviewers with open review requests, the busiest first.
+    fn review_requests(&self) -> Vec<(&String, usize)> {
+        let mut requests: Vec<(&String, usize)> = self.review_requests.iter().map(|(reviewer, count)| (reviewer, *count)).collect();
+        requests.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        requests
+    }
+
+    /// The most active reviewers over `windows`, at most `MAX_REVIEWERS` of them.
+    fn reviewers(&self, windows: &[Window]) -> Vec<String> {
+        let mut totals: BTreeMap<&String, usize> = BTreeMap::new();
+        for stats in windows.iter().filter_map(|w| self.stats(*w)) {
+            for (reviewer, count) in &stats.reviews {
+                *totals.entry(reviewer).or_default() += count;
+            }
+        }
+        let mut reviewers: Vec<(&String, usize)> = totals.into_iter().collect();
+        reviewers.sort_by_key(|(_, total)| std::cmp::Reverse(*total));
+        reviewers.into_iter().take(MAX_REVIEWERS).map(|(reviewer, _)| reviewer.clone()).collect()
+    }
+
+    fn render_labels(&self) -> String {
+        if self.config.labels.breakdown.is_empty() {
+            return String::new();
+        }
+        let windows = self.config.report.windows.as_slice();
+        let labels = windows.iter().map(|w| w.label()).collect::<Vec<String>>().join(" | ");
+        let mut md = format!("# Labels\n\nOpen issues of each label and their median age, then the issues opened / closed with the label in each window, displayed as:\n\n**Label**: open (median age) | {labels}.\n\n");
+        md.push_str(&self.render_label_stats(windows));
+        md
+    }
+
+    /// Label breakdown of a single window, as answered by the `/labels` command.
+    pub fn render_window_labels(&self, window: Window) -> String {
+        if self.config.labels.breakdown.is_empty() {
+            return String::from("No label breakdown is configured, list labels or prefixes such as \"type: *\" in `breakdown` of the `[labels]` section");
+        }
+        let mut md = match self.date_span(window) {
+            Some(span) => format!("# {} labels for {} ({span})\n\n", self.title(), window.label()),
+            None => format!("# {} labels for {}\n\n", self.title(), window.label()),
+        };
+        md.push_str("Open issues of each label and their median age, then the issues opened / closed with the label in the window.\n\n");
+        md.push_str(&self.render_label_stats(&[window]));
+        md
+    }
+
+    fn render_label_stats(&self, windows: &[Window]) -> String {
+        let indices: Vec<usize> = windows.iter().filter_map(|w| self.stats.iter().position(|s| s.window == *w)).collect();
+        self.label_stats.iter()
+            .map(|stats| {
+                let counts = indices.iter().map(|i| format!(" | {} / {}", stats.opened[*i], stats.closed[*i])).collect::<String>();
+                format!("**{}**: [{} open]({}) ({}){counts}\n\n", stats.label, stats.open, self.label_url(&stats.label), stats.median_age().map_or(String::from("-"), format_duration))
+            })
+            .collect()
+    }
+
+    /// Search link to the open issues with `label`.
+    fn label_url(&self, label: &str) -> String {
+        format!("{}is%3Aissue+is%3Aopen+label%3A{}", self.issues_search_url(), encode_query(&format!("\"{label}\"")))
+    }
+
+    fn render_metrics(&self, windows: &[Window], separator: &str) -> String {
+        let row = |f: &dyn Fn(Window, &TimedStats) -> String| windows.iter()
+            .filter_map(|w| self.stats(*w).map(|s| f(*w, s)))
+            .collect::<Vec<String>>().join(separator);
+        let link = |metric: Metric, window: Window, count: usize| format!("[{count}]({})", self.metric_url(metric, window));
+
+        let mut md = format!("## Pull Requests\n\n**Opened PRs**: {}\n\n", row(&|w, s| link(Metric::OpenedPrs, w, s.opened_prs)));
+        md.push_str(&format!("**Merged PRs**: {}\n\n", row(&|w, s| link(Metric::MergedPrs, w, s.merged_prs))));
+        md.push_str(&format!("**Merge Rate**: {}\n\n", row(&|_, s| format!("{:.2}", (s.merged_prs as f64)/(s.opened_prs as f64)))));
+        md.push_str(&format!("**PR Growth**: {}\n\n", row(&|_, s| format!("{}", s.opened_prs as i64 - s.merged_prs as i64))));
+
+        md.push_str(&format!("## Issues\n\n**Opened Issues**: {}\n\n", row(&|w, s| link(Metric::OpenedIssues, w, s.opened_issues))));
+        md.push_str(&format!("**Closed Issues**: {}\n\n", row(&|w, s| link(Metric::ClosedIssues, w, s.closed_issues))));
+        md.push_str(&format!("**Resolution Rate**: {}\n\n", row(&|_, s| format!("{:.2}", (s.closed_issues as f64)/(s.opened_issues as f64)))));
+        md.push_str(&format!("**Issue Growth**: {}\n\n", row(&|_, s| format!("{}", s.opened_issues as i64 - s.closed_issues as i64))));
+        md
+    }
+
+    /// Search link to the items counted by `metric` over `window`.
+    fn metric_url(&self, metric: Metric, window: Window) -> String {
+        let (base, qualifier) = match metric {
+            Metric::OpenedPrs => (format!("{}is%3Apr+sort%3Aupdated-asc", self.pulls_search_url()), CREATED_STRING),
+            Metric::MergedPrs => (format!("{}is%3Apr+is%3Amerged+sort%3Aupdated-asc+draft%3Afalse", self.pulls_search_url()), MERGED_STRING),
+            Metric::OpenedIssues => (format!("{}is%253Aissue+sort%3Aupdated-asc", self.issues_search_url()), CREATED_STRING),
+            Metric::ClosedIssues => (format!("{}is%253Aissue+is%253Aclosed+sort%3Aupdated-asc", self.issues_search_url()), CLOSED_STRING),
+        };
+        match self.search_span(window) {
+            Some(span) => format!("{base}{qualifier}{span}"),
+            None => base,
+        }
+    }
+
+    /// Stats of `window`, if it is configured.
+    fn stats(&self, window: Window) -> Option<&TimedStats> {
+        self.stats.iter().find(|s| s.window == window)
+    }
+
+    /// Date range of `window` in GitHub search syntax, `None` for all time.
+    fn date_span(&self, window: Window) -> Option<String> {
+        match self.stats(window)?.span.days(self.timezone())? {
+            (first, last) if first == last => Some(format!("{first}")),
+            (first, last) => Some(format!("{first}..{last}")),
+        }
+    }
+
+    /// Encoded date range of `window` for search links. GitHub reads dates as UTC, so windows not starting at
+    /// midnight UTC need their exact bounds.
+    fn search_span(&self, window: Window) -> Option<String> {
+        let timezone = self.timezone();
+        let span = self.stats(window)?.span;
+        let start = span.start?;
+        if timezone == Tz::UTC && start.time() == NaiveTime::MIN {
+            return self.date_span(window);
+        }
+        let bound = |date: DateTime<Utc>| date.with_timezone(&timezone).format("%Y-%m-%dT%H:%M:%S%:z");
+        Some(encode_query(&format!("{}..{}", bound(start), bound(span.end - TimeDelta::seconds(1)))))
+    }
+}
+
+/// Meta key of the latest release of `repository`, kept for reports computed from the cache.
+fn release_key(repository: &Repository) -> String {
+    format!("latest_release:{repository}")
+}
+
+/// Meta key of the day `repository` last had every issue fetched, in the report timezone.
+fn full_sync_key(repository: &Repository) -> String {
+    format!("full_sync:{repository}")
+}
+
+fn search_qualifiers(repositories: &[Repository]) -> String {
+    repositories.iter().map(|r| format!("repo%3A{}%2F{}+", r.owner, r.name)).collect()
+}
+
+fn label_names(labels: &[Label]) -> Vec<String> {
+    labels.iter().map(|l| l.name.clone()).collect()
 }
 
 fn parse_issue(issue: Issue) -> ParsedIssue {
     ParsedIssue {
         user: issue.user.login.clone(),
         id: issue.number,
+        url: issue.html_url.to_string(),
         title: issue.title.clone(),
         state: issue.state,
         creation_date: issue.created_at,
         updated_date: issue.updated_at,
         closed_date: issue.closed_at,
-        labels: issue.labels.clone()
+        labels: label_names(&issue.labels)
     }
 }
 
-fn parse_pr(pr: PullRequest) -> ParsedPR {
-    //println!("{:#?}", pr);
-    ParsedPR {
-        user: pr.user.expect("Failed getting pr user").login,
+/// State of a pull request from its own fields: a closed draft is closed rather than a draft. Open pull requests
+/// ready for review are then judged by their reviews, see `ParsedPR::with_reviews`.
+fn pr_state(open: bool, draft: bool, merged: bool) -> PRState {
+    match (open, draft, merged) {
+        (_, _, true) => PRState::Merged,
+        (false, _, false) => PRState::Closed,
+        (true, true, false) => PRState::Draft,
+        (true, false, false) => PRState::Ready,
+    }
+}
+
+/// Fails on the first missing field, naming it.
+fn parse_pr(pr: PullRequest, repository: &Repository) -> Result<ParsedPR, String> {
+    let missing = |field: &str| format!("pull request has no {field}");
+    let open_state = pr.state.ok_or_else(|| missing("state"))?;
+    Ok(ParsedPR {
+        // Deleted accounts become GitHub's ghost user, as in the GraphQL API
+        user: pr.user.map_or_else(|| String::from("ghost"), |u| u.login),
         id: pr.number,
-        title: pr.title.expect("Failed getting pr title"),
-        state: match pr.draft {
-            Some(true) => PRState::Draft,
-            _ => match pr.merged_at {
-                Some(_) => PRState::Merged,
-                _ => match pr.closed_at {
-                    Some(_) => PRState::Cancelled,
-                    _=> PRState::Open
-                }
-            }
-        },
-        open_state: pr.state.expect("Failed getting pr state"),
-        creation_date: pr.created_at.expect("Failed getting pr creation date"),
-        updated_date: pr.updated_at.expect("Failed getting pr update date"),
+        url: pr.html_url.map(|u| u.to_string()).unwrap_or_else(|| repository.pull_url(pr.number)),
+        title: pr.title.ok_or_else(|| missing("title"))?,
+        state: pr_state(open_state == IssueState::Open, pr.draft == Some(true), pr.merged_at.is_some()),
+        open_state,
+        creation_date: pr.created_at.ok_or_else(|| missing("creation date"))?,
+        updated_date: pr.updated_at.ok_or_else(|| missing("update date"))?,
         closed_date: pr.closed_at,
-        labels: pr.labels.expect("Failed getting pr labels")
-    }
+        labels: label_names(&pr.labels.unwrap_or_default()),
+        requested_reviewers: pr.requested_reviewers.unwrap_or_default().into_iter().map(|u| u.login)
+            .chain(pr.requested_teams.unwrap_or_default().into_iter().map(|t| format!("{}/{}", repository.owner, t.slug)))
+            .collect(),
+        reviews: Vec::new(),
+    })
+}
+
+/// Submitted reviews only, pending ones have no verdict yet.
+fn parse_review(review: Review) -> Option<ParsedReview> {
+    let state = match review.state? {
+        pulls::ReviewState::Approved => ReviewState::Approved,
+        pulls::ReviewState::ChangesRequested => ReviewState::ChangesRequested,
+        pulls::ReviewState::Commented => ReviewState::Commented,
+        pulls::ReviewState::Dismissed => ReviewState::Dismissed,
+        _ => return None,
+    };
+    Some(ParsedReview {
+        reviewer: review.user.map_or_else(|| String::from("ghost"), |u| u.login),
+        state,
+        submitted_date: review.submitted_at?,
+    })
 }
 
-fn parse_pr_from_issue(pr: Issue) -> ParsedPR {
+/// Pull request of a search result, whose draft flag and merge date the issue model leaves out.
+fn parse_pr_from_issue(pr: Issue, draft: bool, merged: bool) -> ParsedPR {
     ParsedPR {
         user: pr.user.login,
         id: pr.number,
+        url: pr.html_url.to_string(),
         title: pr.title,
-        state: PRState::Open,
+        state: pr_state(pr.state == IssueState::Open, draft, merged),
         open_state: pr.state,
         creation_date: pr.created_at,
         updated_date: pr.updated_at,
         closed_date: pr.closed_at,
-        labels: pr.labels
+        labels: label_names(&pr.labels),
+        requested_reviewers: Vec::new(),
+        reviews: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use crate::{clock::FixedClock, github::*};
+
+    async fn fixture_report() -> GithubData {
+        fixture_report_with(Config::default()).await
+    }
+
+    async fn fixture_report_with(config: Config) -> GithubData {
+        let database = Database::open_in_memory().unwrap();
+        let source = FixtureSource::new(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures"));
+        let mut report = GithubData::new(Repository::new("octo", "cat"), Arc::new(config));
+        report.fetch(&database, &source, &FixedClock(Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap())).await.unwrap();
+        report
+    }
+
+    #[tokio::test]
+    async fn open_items_are_classified() {
+        let report = fixture_report().await;
+        // An issue both confirmed and unconfirmed counts as unconfirmed, the first category
+        assert_eq!(report.category_issues, vec![2, 1, 1, 0]);
+        // #5 has no label but is still open
+        assert_eq!(report.uncategorised_issues.iter().map(|i| i.id).collect::<Vec<u64>>(), vec![5]);
+        assert_eq!(report.open_issues, 5);
+        assert_eq!(report.ready_pull_requests, 1);
+        assert_eq!(report.draft_pull_requests, 1);
+        assert_eq!(report.warnings(), ["Skipped octo/cat#13: pull request has no title"]);
+    }
+
+    #[tokio::test]
+    async fn timed_stats_count_from_the_fetch_date() {
+        let report = fixture_report().await;
+        let counts = |s: &TimedStats| (s.opened_prs, s.merged_prs, s.cancelled_prs, s.opened_issues, s.closed_issues);
+        assert_eq!(counts(report.stats(Window::Yesterday).unwrap()), (1, 1, 0, 1, 1));
+        assert_eq!(counts(report.stats(Window::LastDays(7)).unwrap()), (2, 1, 1, 2, 1));
+        assert_eq!(counts(report.stats(Window::LastDays(365)).unwrap()), (5, 2, 1, 6, 1));
+        assert_eq!(counts(report.stats(Window::All).unwrap()), (5, 2, 1, 7, 2));
+    }
+
+    #[tokio::test]
+    async fn latency_covers_items_resolved_in_the_window() {
+        let report = fixture_report().await;
+        let week = report.stats(Window::LastDays(7)).unwrap();
+        // #10 was merged after 6 days and 2 hours, #4 closed after 17 days and 5 hours
+        assert_eq!(week.time_to_merge().map(|l| (l.count, l.median)), Some((1, TimeDelta::hours(6*24 + 2))));
+        assert_eq!(week.time_to_close().map(|l| (l.count, l.p90)), Some((1, TimeDelta::hours(17*24 + 5))));
+        let all = report.stats(Window::All).unwrap().time_to_close().unwrap();
+        assert_eq!((all.count, all.median), (2, TimeDelta::minutes((19*24 + 14)*60 + 30)));
+        assert_eq!(report.stats(Window::Yesterday).unwrap().time_to_merge().unwrap().count, 1);
+
+        let md = report.render();
+        assert!(md.contains("# Latency"));
+        assert!(md.contains("**Median**: 6d 2h | 6d 2h | 6d 2h | 18d 1h | 18d 1h"));
+        assert!(report.render_window_latency(Window::Yesterday).starts_with("# [octo/cat](https://github.com/octo/cat) latency for yesterday (2026-03-09)"));
+    }
+
+    #[tokio::test]
+    async fn reviews_are_counted_per_window() {
+        let report = fixture_report().await;
+        assert_eq!(report.waiting_pull_requests.iter().map(|p| p.id).collect::<Vec<u64>>(), vec![8]);
+        assert_eq!(report.review_requests(), vec![(&String::from("mew"), 1), (&String::from("octo/reviewers"), 1)]);
+        let reviews = |window: Window| report.stats(window).unwrap().reviews.clone().into_iter().collect::<Vec<(String, usize)>>();
+        assert_eq!(reviews(Window::Yesterday), vec![(String::from("jirachi"), 1)]);
+        // The comment of mew on their own #10 is not a review
+        assert_eq!(reviews(Window::LastDays(7)), vec![(String::from("jirachi"), 2)]);
+        assert_eq!(reviews(Window::All), vec![(String::from("jirachi"), 3), (String::from("mew"), 1)]);
+        // #10 was first reviewed after 2 days, #11 after 1 day and 6 hours, #12 after 1 day
+        assert_eq!(report.stats(Window::Yesterday).unwrap().time_to_first_review(), None);
+        let all = report.stats(Window::All).unwrap().time_to_first_review().unwrap();
+        assert_eq!((all.count, all.median), (3, TimeDelta::hours(30)));
+
+        let md = report.render();
+        assert!(md.contains("### [1 Waiting on Review]"));
+        assert!(md.contains("* **octo/reviewers**: 1\n"));
+        assert!(md.contains("**jirachi**: 1 | 2 | 2 | 3 | 3\n\n**mew**: 0 | 0 | 1 | 1 | 1\n\n"));
+        assert!(md.contains("## Time to First Review\n\n**Median**: - | 2d 0h | 1d 15h | 1d 6h | 1d 6h\n\n"));
+
+        let mut earlier = GithubData::new(Repository::new("octo", "cat"), Arc::new(Config::default()));
+        let database = Database::open_in_memory().unwrap();
+        let source = FixtureSource::new(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures"));
+        earlier.fetch(&database, &source, &FixedClock(Utc.with_ymd_and_hms(2026, 3, 4, 12, 0, 0).unwrap())).await.unwrap();
+        // #10 was ready and not reviewed yet, as the comment of its author does not count
+        assert_eq!(earlier.waiting_pull_requests.iter().map(|p| p.id).collect::<Vec<u64>>(), vec![10]);
+    }
+
+    #[tokio::test]
+    async fn issues_fall_in_their_first_category() {
+        let mut config = Config::default();
+        let pattern = |p: &str| p.parse::<LabelPattern>().unwrap();
+        config.labels.categories = vec![
+            Category { name: String::from("Confirmed Bugs"), any: Vec::new(), all: vec![pattern("bug"), pattern("status: confirmed")], none: vec![pattern("status: unconfirmed")] },
+            Category { name: String::from("Bugs"), any: vec![pattern("bug")], all: Vec::new(), none: Vec::new() },
+        ];
+        let report = fixture_report_with(config).await;
+        // #7 is confirmed but also unconfirmed
+        assert_eq!(report.category_issues, vec![1, 2]);
+        assert_eq!(report.uncategorised_issues.iter().map(|i| i.id).collect::<Vec<u64>>(), vec![5, 3]);
+        let snapshot = report.snapshot();
+        assert_eq!((snapshot.categories[1].clone(), snapshot.uncategorised_issues), ((String::from("Bugs"), 2), 2));
+
+        let md = report.render();
+        assert!(md.contains("[1 Confirmed Bugs](https://github.com/octo/cat/issues?q=is%3Aissue+is%3Aopen+label%3A%22bug%22+label%3A%22status%3A+confirmed%22+-label%3A%22status%3A+unconfirmed%22) / "));
+        assert!(md.contains(" / 2 Uncategorised)\n"));
+        assert!(md.contains("# Triage\n\n### 2 Uncategorised Issues\n* [#5 - "));
+    }
+
+    #[tokio::test]
+    async fn labels_are_broken_down() {
+        let mut config = Config::default();
+        config.labels.breakdown = vec!["status: *".parse().unwrap(), "bug".parse().unwrap()];
+        let report = fixture_report_with(config).await;
+        let stats = |s: &LabelStats| (s.label.clone(), s.open, s.opened.clone(), s.closed.clone());
+        assert_eq!(report.label_stats.iter().map(stats).collect::<Vec<_>>(), vec![
+            (String::from("status: confirmed"), 2, vec![0, 0, 2, 3, 3], vec![1, 1, 1, 1, 1]),
+            (String::from("status: unconfirmed"), 2, vec![1, 1, 2, 2, 2], vec![0, 0, 0, 0, 0]),
+            (String::from("bug"), 3, vec![1, 1, 3, 4, 4], vec![1, 1, 1, 1, 1]),
+        ]);
+        // #1 has been open for 54 days and 2 hours, #7 for 10 days and 2 hours
+        assert_eq!(report.label_stats[0].median_age(), Some(TimeDelta::hours(32*24 + 2)));
+
+        let md = report.render();
+        assert!(md.contains("**status: unconfirmed**: [2 open](https://github.com/octo/cat/issues?q=is%3Aissue+is%3Aopen+label%3A%22status%3A+unconfirmed%22) (5d 15h) | 1 / 0 | 1 / 0 | 2 / 0 | 2 / 0 | 2 / 0\n\n"));
+        assert!(report.render_window_labels(Window::Yesterday).contains("**bug**: [3 open]"));
+        assert!(!fixture_report().await.render().contains("# Labels"));
+    }
+
+    #[tokio::test]
+    async fn configured_windows_are_counted() {
+        let mut config = Config::default();
+        config.report.windows = vec![Window::Today, Window::Week, Window::SinceRelease];
+        let report = fixture_report_with(config).await;
+        let counts = |s: &TimedStats| (s.opened_prs, s.merged_prs, s.cancelled_prs, s.opened_issues, s.closed_issues);
+        assert_eq!(report.stats.iter().map(counts).collect::<Vec<_>>(), vec![(0, 0, 0, 0, 0), (1, 1, 0, 1, 1), (1, 1, 1, 2, 1)]);
+        assert!(report.render().contains("**Metric**: today | this week | since the latest release."));
+        // v1.2.0 was published at 10:00 UTC, so the search needs the exact time
+        assert!(report.metric_url(Metric::OpenedIssues, Window::SinceRelease)
+            .ends_with("2026-03-04T10%3A00%3A00%2B00%3A00..2026-03-10T23%3A59%3A59%2B00%3A00"));
+        assert!(report.stats(Window::Yesterday).is_none());
+    }
+
+    #[tokio::test]
+    async fn days_start_at_midnight_in_the_timezone() {
+        let mut config = Config::default();
+        config.report.timezone = chrono_tz::Asia::Tokyo;
+        let report = fixture_report_with(config).await;
+        // #4 was closed at 15:00 UTC, midnight in Tokyo
+        let counts = |s: &TimedStats| (s.opened_prs, s.merged_prs, s.cancelled_prs, s.opened_issues, s.closed_issues);
+        assert_eq!(counts(report.stats(Window::Yesterday).unwrap()), (1, 1, 0, 1, 0));
+        assert!(report.metric_url(Metric::ClosedIssues, Window::Yesterday)
+            .ends_with("2026-03-09T00%3A00%3A00%2B09%3A00..2026-03-09T23%3A59%3A59%2B09%3A00"));
+        assert_eq!(report.date_span(Window::LastDays(7)).unwrap(), "2026-03-02..2026-03-10");
+    }
+
+    #[tokio::test]
+    async fn report_is_computed_as_of_an_earlier_instant() {
+        let database = Database::open_in_memory().unwrap();
+        let source = FixtureSource::new(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures"));
+        let config = Arc::new(Config::default());
+        let mut latest = GithubData::new(Repository::new("octo", "cat"), Arc::clone(&config));
+        latest.fetch(&database, &source, &FixedClock(Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap())).await.unwrap();
+
+        let mut report = GithubData::new(Repository::new("octo", "cat"), config);
+        report.compute(&database, &FixedClock(Utc.with_ymd_and_hms(2026, 3, 6, 12, 0, 0).unwrap())).unwrap();
+        // #4 was still open, #2 did not exist yet
+        assert_eq!(report.category_issues, vec![1, 2, 1, 0]);
+        // #10 was not merged yet, #11 was already closed and #8 did not exist yet
+        assert_eq!((report.ready_pull_requests, report.draft_pull_requests), (1, 1));
+        let counts = |s: &TimedStats| (s.opened_prs, s.merged_prs, s.cancelled_prs, s.opened_issues, s.closed_issues);
+        assert_eq!(counts(report.stats(Window::Yesterday).unwrap()), (0, 0, 0, 1, 0));
+        assert_eq!(counts(report.stats(Window::All).unwrap()), (4, 1, 1, 6, 1));
+    }
+
+    #[tokio::test]
+    async fn deleted_issues_leave_the_cache_on_the_daily_resync() {
+        async fn fetch(database: &Database, day: u32) {
+            let source = FixtureSource::new(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures"));
+            let mut report = GithubData::new(Repository::new("octo", "cat"), Arc::new(Config::default()));
+            report.fetch(database, &source, &FixedClock(Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap())).await.unwrap();
+        }
+        let database = Database::open_in_memory().unwrap();
+        let repository = Repository::new("octo", "cat");
+        let deleted = ParsedIssue { id: 99, ..test_support::issue(Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap(), None) };
+        let cached = || database.issues(&repository).unwrap().iter().any(|i| i.id == 99);
+
+        database.save_issues(&repository, std::slice::from_ref(&deleted)).unwrap();
+        fetch(&database, 10).await;
+        assert!(!cached());
+        // Later runs of the day only fetch the updated issues
+        database.save_issues(&repository, &[deleted]).unwrap();
+        fetch(&database, 10).await;
+        assert!(cached());
+        fetch(&database, 11).await;
+        assert!(!cached());
+    }
+
+    #[tokio::test]
+    async fn render_shows_counts_and_lists() {
+        let report = fixture_report().await;
+        let md = report.render();
+        assert!(md.starts_with("# [octo/cat](https://github.com/octo/cat)\n"));
+        assert!(md.contains("Wrong damage with critical hits"));
+        assert!(md.contains("Add running shoes indoors"));
+        assert!(md.contains("Skipped octo/cat#13"));
+        assert_eq!(report.stale_issues.len(), 3);
+        assert_eq!(report.most_recent_pull_requests.iter().map(|p| p.id).collect::<Vec<u64>>(), vec![8, 9]);
+        assert_eq!(report.most_recent_pull_requests.iter().map(|p| p.state).collect::<Vec<PRState>>(), vec![PRState::Ready, PRState::Draft]);
+    }
+
+    #[test]
+    fn pull_request_states_follow_the_lifecycle() {
+        assert_eq!(pr_state(true, true, false), PRState::Draft);
+        assert_eq!(pr_state(true, false, false), PRState::Ready);
+        assert_eq!(pr_state(false, false, true), PRState::Merged);
+        assert_eq!(pr_state(false, false, false), PRState::Closed);
+        // A closed draft is closed, not a draft
+        assert_eq!(pr_state(false, true, false), PRState::Closed);
+    }
+
+    #[test]
+    fn reviews_decide_the_state_of_ready_pull_requests() {
+        let day = |day: u32| Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap();
+        let review = |reviewer: &str, state: ReviewState, submitted: u32| ParsedReview { reviewer: reviewer.to_string(), state, submitted_date: day(submitted) };
+        let pull_request = |state: PRState, reviews: Vec<ParsedReview>| test_support::pull_request(day(1), None, state).with_reviews(reviews);
+
+        let approved = pull_request(PRState::Ready, vec![review("mew", ReviewState::Approved, 2), review("celebi", ReviewState::Commented, 3)]);
+        assert_eq!(approved.state, PRState::Approved);
+        assert_eq!(approved.state_at(day(1)), PRState::Ready);
+        let changes = pull_request(PRState::Ready, vec![review("mew", ReviewState::Approved, 2), review("celebi", ReviewState::ChangesRequested, 3)]);
+        assert_eq!(changes.state, PRState::ChangesRequested);
+        assert_eq!(changes.state_at(day(2)), PRState::Approved);
+        // celebi approved after requesting changes, and a dismissed approval withdraws it
+        let addressed = pull_request(PRState::Ready, vec![review("celebi", ReviewState::ChangesRequested, 2), review("celebi", ReviewState::Approved, 3)]);
+        assert_eq!(addressed.state, PRState::Approved);
+        assert_eq!(pull_request(PRState::Ready, vec![review("mew", ReviewState::Dismissed, 2)]).state, PRState::Ready);
+        assert_eq!(pull_request(PRState::Ready, vec![review("octocat", ReviewState::Approved, 2)]).state, PRState::Ready);
+        // Reviews decide neither drafts nor closed pull requests
+        let draft = pull_request(PRState::Draft, vec![review("mew", ReviewState::Approved, 2)]);
+        assert_eq!((draft.state, draft.state_at(day(3))), (PRState::Draft, PRState::Draft));
+        assert_eq!(pull_request(PRState::Merged, vec![review("mew", ReviewState::ChangesRequested, 2)]).state, PRState::Merged);
+    }
+
+    #[test]
+    fn malformed_pull_request_is_reported() {
+        let pull_request = |title: Option<&str>| serde_json::from_value::<PullRequest>(serde_json::json!({
+            "url": "https://api.github.com/repos/octo/cat/pulls/5",
+            "id": 1,
+            "number": 5,
+            "title": title,
+            "state": "open",
+            "created_at": "2026-03-01T12:00:00Z",
+            "updated_at": "2026-03-01T12:00:00Z",
+            "locked": false,
+            "maintainer_can_modify": false,
+            "head": { "ref": "fix", "sha": "abc" },
+            "base": { "ref": "master", "sha": "def" },
+        })).unwrap();
+        // A missing author becomes the ghost user, as with the GraphQL API
+        assert_eq!(parse_pr(pull_request(Some("Fix")), &Repository::new("octo", "cat")).unwrap().user, "ghost");
+        let why = parse_pr(pull_request(None), &Repository::new("octo", "cat")).unwrap_err();
+        assert_eq!(why, "pull request has no title");
     }
 }
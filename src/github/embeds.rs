@@ -0,0 +1,359 @@
+use serenity::all::{CreateEmbed, CreateEmbedFooter};
+
+use crate::{github::{GithubData, Latency, Metric, TimedStats, APPROVED_QUERY, CHANGES_REQUESTED_QUERY, READY_QUERY}, utils::format_duration};
+
+static EMBEDS_PER_MESSAGE: usize = 10;
+static MESSAGE_LENGTH: usize = 6000;
+static FIELDS_PER_EMBED: usize = 25;
+static TITLE_LENGTH: usize = 256;
+static DESCRIPTION_LENGTH: usize = 4096;
+static FIELD_NAME_LENGTH: usize = 256;
+static FIELD_VALUE_LENGTH: usize = 1024;
+static CONTENT_LENGTH: usize = 2000;
+/// Room kept at the end of a cut message to tell how many lines were left out.
+static CUT_NOTE_LENGTH: usize = 24;
+
+static RAW_STATS_COLOUR: u32 = 0x5865F2;
+static STALES_COLOUR: u32 = 0xE67E22;
+static LAST_CREATED_COLOUR: u32 = 0x2ECC71;
+static STATS_COLOUR: u32 = 0x9B59B6;
+static LATENCY_COLOUR: u32 = 0x1ABC9C;
+static REVIEWS_COLOUR: u32 = 0xF1C40F;
+static LABELS_COLOUR: u32 = 0x3498DB;
+
+/// An embed whose length can be checked against Discord's limits before it is built.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Embed {
+    pub title: String,
+    pub url: Option<String>,
+    pub description: Option<String>,
+    pub colour: u32,
+    pub fields: Vec<(String, String, bool)>,
+    pub footer: Option<String>,
+}
+
+impl Embed {
+    fn new(title: impl Into<String>, url: Option<String>, colour: u32) -> Self {
+        Self { title: title.into(), url, colour, ..Self::default() }
+    }
+
+    fn field(mut self, name: impl Into<String>, value: impl Into<String>, inline: bool) -> Self {
+        let value = value.into();
+        let value = if value.is_empty() { String::from("None") } else { value };
+        self.fields.push((truncate(&name.into(), FIELD_NAME_LENGTH), truncate(&value, FIELD_VALUE_LENGTH), inline));
+        self
+    }
+
+    /// Number of characters counted by Discord against the message limit.
+    pub fn len(&self) -> usize {
+        self.title.chars().count()
+            + self.description.as_ref().map_or(0, |d| d.chars().count())
+            + self.fields.iter().map(|(name, value, _)| name.chars().count() + value.chars().count()).sum::<usize>()
+            + self.footer.as_ref().map_or(0, |f| f.chars().count())
+    }
+
+    pub fn build(&self) -> CreateEmbed {
+        let mut embed = CreateEmbed::new()
+            .title(&self.title)
+            .colour(self.colour)
+            .fields(self.fields.clone());
+        if let Some(url) = &self.url {
+            embed = embed.url(url);
+        }
+        if let Some(description) = &self.description {
+            embed = embed.description(description);
+        }
+        if let Some(footer) = &self.footer {
+            embed = embed.footer(CreateEmbedFooter::new(footer));
+        }
+        embed
+    }
+
+    /// Splits the embed into continuation embeds until each one fits in a message.
+    fn split(mut self) -> Vec<Embed> {
+        self.title = truncate(&self.title, TITLE_LENGTH);
+        self.description = self.description.map(|d| truncate(&d, DESCRIPTION_LENGTH));
+        if self.fields.len() <= FIELDS_PER_EMBED && self.len() <= MESSAGE_LENGTH {
+            return vec![self];
+        }
+
+        let continued = Embed {
+            title: truncate(&format!("{} (continued)", self.title), TITLE_LENGTH),
+            url: self.url.clone(),
+            colour: self.colour,
+            footer: self.footer.clone(),
+            ..Embed::default()
+        };
+        let mut embeds = vec![Embed { fields: Vec::new(), ..self.clone() }];
+        for field in self.fields {
+            let current = embeds.last_mut().unwrap();
+            let field_length = field.0.chars().count() + field.1.chars().count();
+            if current.fields.len() == FIELDS_PER_EMBED || current.len() + field_length > MESSAGE_LENGTH {
+                embeds.push(continued.clone());
+            }
+            embeds.last_mut().unwrap().fields.push(field);
+        }
+        embeds
+    }
+}
+
+/// Groups embeds into messages that respect Discord's per-message limits.
+pub fn paginate(embeds: Vec<Embed>) -> Vec<Vec<Embed>> {
+    let mut messages: Vec<Vec<Embed>> = Vec::new();
+    let mut length = 0;
+    for embed in embeds.into_iter().flat_map(Embed::split) {
+        match messages.last_mut() {
+            Some(message) if message.len() < EMBEDS_PER_MESSAGE && length + embed.len() <= MESSAGE_LENGTH => {
+                length += embed.len();
+                message.push(embed);
+            },
+            _ => {
+                length = embed.len();
+                messages.push(vec![embed]);
+            },
+        }
+    }
+    messages
+}
+
+/// Cuts `text` to at most `limit` characters, on a line boundary when possible.
+fn truncate(text: &str, limit: usize) -> String {
+    if text.chars().count() <= limit {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(limit - 1).collect();
+    match cut.rfind('\n') {
+        Some(line_end) if line_end > 0 => format!("{}\n…", &cut[..line_end]),
+        _ => format!("{cut}…"),
+    }
+}
+
+/// Cuts `text` on a line boundary to fit in the content of a message, ending it with how many lines were left out.
+pub fn fit_message(text: &str) -> String {
+    if text.chars().count() <= CONTENT_LENGTH {
+        return text.to_string();
+    }
+    let lines = |text: &str| text.lines().filter(|l| !l.trim().is_empty()).count();
+    let cut = truncate(text, CONTENT_LENGTH - CUT_NOTE_LENGTH);
+    let kept = cut.trim_end_matches('…').trim_end();
+    format!("{kept}\n\n… and {} more", lines(text) - lines(kept))
+}
+
+impl GithubData {
+    /// Renders the report as embeds: raw stats, stales, last created, stats, latency, reviews and, when configured, labels.
+    pub fn render_embeds(&self) -> Vec<Embed> {
+        let url = match self.repositories.as_slice() {
+            [repository] => Some(repository.url()),
+            _ => None,
+        };
+        let footer = format!("{} • {}", self.name(), self.date.format("%Y-%m-%d %H:%M UTC"));
+        let mut embeds = vec![
+            self.raw_stats_embed(url),
+            self.stales_embed(),
+            self.last_created_embed(),
+            self.stats_embed(),
+            self.latency_embed(),
+            self.reviews_embed(),
+        ];
+        if !self.config.labels.breakdown.is_empty() {
+            embeds.push(self.labels_embed());
+        }
+        for embed in embeds.iter_mut() {
+            embed.footer = Some(footer.clone());
+        }
+        embeds
+    }
+
+    fn raw_stats_embed(&self, url: Option<String>) -> Embed {
+        let issues = self.issues_search_url();
+        let prs = self.pulls_search_url();
+
+        let mut embed = Embed::new(format!("Raw Stats for {} (Currently Open)", self.name()), url, RAW_STATS_COLOUR);
+        if !self.warnings.is_empty() {
+            embed.description = Some(self.warnings.iter().map(|w| format!("⚠️ {w}\n")).collect());
+        }
+        embed = embed.field("Issues", format!("[{} open]({issues}is%3Aissue+is%3Aopen)", self.open_issues), true);
+        for (category, count) in self.categories() {
+            let value = match self.category_url(category) {
+                Some(url) => format!("[{count}]({url})"),
+                None => count.to_string(),
+            };
+            embed = embed.field(&category.name, value, true);
+        }
+        embed
+            .field("Uncategorised", self.uncategorised_issues.len().to_string(), true)
+            .field("Pull Requests", format!("[{} open]({prs}is%3Apr+is%3Aopen)", self.open_pull_requests), true)
+            .field("Ready for Review", format!("[{}]({prs}{READY_QUERY})", self.ready_pull_requests), true)
+            .field("Approved", format!("[{}]({prs}{APPROVED_QUERY})", self.approved_pull_requests), true)
+            .field("Changes Requested", format!("[{}]({prs}{CHANGES_REQUESTED_QUERY})", self.changes_requested_pull_requests), true)
+            .field("Draft", format!("[{}]({prs}is%3Apr+is%3Aopen+draft%3Atrue)", self.draft_pull_requests), true)
+    }
+
+    fn stales_embed(&self) -> Embed {
+        Embed::new("Stales", Some(format!("{}is%3Apr+is%3Aopen+draft%3Afalse+sort%3Aupdated-asc", self.pulls_search_url())), STALES_COLOUR)
+            .field("Pull Requests", self.stale_pull_requests.iter().map(|p| p.list_render(self.date, self.timestamps())).collect::<String>(), false)
+            .field("Bugs", self.stale_issues.iter().map(|i| i.list_render(self.date, self.timestamps())).collect::<String>(), false)
+            .field(format!("{} Uncategorised Issues", self.uncategorised_issues.len()),
+                self.uncategorised_issues.iter().take(self.config.lists.stale as usize).map(|i| i.list_render(self.date, self.timestamps())).collect::<String>(), false)
+    }
+
+    fn last_created_embed(&self) -> Embed {
+        Embed::new("Last Created", Some(format!("{}is%3Apr+is%3Aopen+sort%3Acreated-desc", self.pulls_search_url())), LAST_CREATED_COLOUR)
+            .field("Pull Requests", self.most_recent_pull_requests.iter().map(|p| p.list_render(self.date, self.timestamps())).collect::<String>(), false)
+            .field("Issues", self.most_recent_issues.iter().map(|i| i.list_render(self.date, self.timestamps())).collect::<String>(), false)
+    }
+
+    fn stats_embed(&self) -> Embed {
+        let mut embed = Embed::new("Stats", None, STATS_COLOUR);
+        embed.description = Some(String::from("Rate is \"For every X created, how many are completed?\". Growth is how many more were opened than completed."));
+        for stats in &self.stats {
+            let window = stats.window;
+            let linked = self.window_field(stats, true);
+            let value = if linked.chars().count() <= FIELD_VALUE_LENGTH { linked } else { self.window_field(stats, false) };
+            let name = match self.date_span(window) {
+                Some(span) => format!("{} ({span})", capitalize(&window.label())),
+                None => capitalize(&window.label()),
+            };
+            embed = embed.field(name, value, true);
+        }
+        embed
+    }
+
+    fn latency_embed(&self) -> Embed {
+        let mut embed = Embed::new("Latency", None, LATENCY_COLOUR);
+        embed.description = Some(String::from("Time from opening to the first review and to merging or closing, as median / 75th percentile / 90th percentile (mean), of the pull requests first reviewed or merged and the issues closed in each window."));
+        for stats in &self.stats {
+            let summary = |latency: Option<Latency>| match latency {
+                Some(l) => format!("{} / {} / {} ({}), {} items", format_duration(l.median), format_duration(l.p75), format_duration(l.p90), format_duration(l.mean), l.count),
+                None => String::from("-"),
+            };
+            let name = match self.date_span(stats.window) {
+                Some(span) => format!("{} ({span})", capitalize(&stats.window.label())),
+                None => capitalize(&stats.window.label()),
+            };
+            let value = format!("**Time to First Review**: {}\n**Time to Merge**: {}\n**Time to Close**: {}",
+                summary(stats.time_to_first_review()), summary(stats.time_to_merge()), summary(stats.time_to_close()));
+            embed = embed.field(name, value, true);
+        }
+        embed
+    }
+
+    fn reviews_embed(&self) -> Embed {
+        let url = format!("{}is%3Apr+is%3Aopen+draft%3Afalse+review%3Anone+sort%3Acreated-asc", self.pulls_search_url());
+        let waiting = self.waiting_pull_requests.iter().take(self.config.lists.stale as usize)
+            .map(|p| p.list_render(self.date, self.timestamps()))
+            .collect::<String>();
+        let requests = self.review_requests().iter().map(|(reviewer, count)| format!("**{reviewer}**: {count}\n")).collect::<String>();
+        let mut embed = Embed::new("Reviews", Some(url), REVIEWS_COLOUR)
+            .field(format!("{} Waiting on Review", self.waiting_pull_requests.len()), waiting, false)
+            .field("Open Review Requests", requests, false);
+        for stats in &self.stats {
+            let name = match self.date_span(stats.window) {
+                Some(span) => format!("{} ({span})", capitalize(&stats.window.label())),
+                None => capitalize(&stats.window.label()),
+            };
+            let value = self.reviewers(&[stats.window]).iter().map(|r| format!("**{r}**: {}\n", stats.reviews[r])).collect::<String>();
+            embed = embed.field(name, value, true);
+        }
+        embed
+    }
+
+    fn labels_embed(&self) -> Embed {
+        let mut embed = Embed::new("Labels", None, LABELS_COLOUR);
+        embed.description = Some(String::from("Open issues of each label and their median age, then the issues opened / closed with the label in each window."));
+        for label in &self.label_stats {
+            let age = label.median_age().map_or(String::from("-"), format_duration);
+            let mut value = format!("**Open**: [{}]({}), median age {age}", label.open, self.label_url(&label.label));
+            for (i, stats) in self.stats.iter().enumerate() {
+                value.push_str(&format!("\n**{}**: {} / {}", capitalize(&stats.window.label()), label.opened[i], label.closed[i]));
+            }
+            embed = embed.field(&label.label, value, true);
+        }
+        embed
+    }
+
+    fn window_field(&self, stats: &TimedStats, linked: bool) -> String {
+        let count = |metric: Metric, count: usize| match linked {
+            true => format!("[{count}]({})", self.metric_url(metric, stats.window)),
+            false => count.to_string(),
+        };
+        [
+            format!("**Opened PRs**: {}", count(Metric::OpenedPrs, stats.opened_prs)),
+            format!("**Merged PRs**: {}", count(Metric::MergedPrs, stats.merged_prs)),
+            format!("**Merge Rate**: {:.2}", (stats.merged_prs as f64)/(stats.opened_prs as f64)),
+            format!("**PR Growth**: {}", stats.opened_prs as i64 - stats.merged_prs as i64),
+            format!("**Opened Issues**: {}", count(Metric::OpenedIssues, stats.opened_issues)),
+            format!("**Closed Issues**: {}", count(Metric::ClosedIssues, stats.closed_issues)),
+            format!("**Resolution Rate**: {:.2}", (stats.closed_issues as f64)/(stats.opened_issues as f64)),
+            format!("**Issue Growth**: {}", stats.opened_issues as i64 - stats.closed_issues as i64),
+        ].join("\n")
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::github::embeds::*;
+
+    fn embed_with_fields(count: usize, value_length: usize) -> Embed {
+        let mut embed = Embed::new("Title", None, 0);
+        for i in 0..count {
+            embed = embed.field(format!("Field {i}"), "x".repeat(value_length), true);
+        }
+        embed
+    }
+
+    #[test]
+    fn field_values_are_truncated_on_lines() {
+        let value = (0..200).map(|i| format!("* line {i}\n")).collect::<String>();
+        let embed = Embed::new("Title", None, 0).field("Name", value, false);
+        let (_, value, _) = &embed.fields[0];
+        assert!(value.chars().count() <= FIELD_VALUE_LENGTH);
+        assert!(value.ends_with("\n…"));
+        assert!(value.lines().rev().nth(1).unwrap().starts_with("* line"));
+    }
+
+    #[test]
+    fn long_messages_are_cut_on_lines() {
+        let text = (0..200).map(|i| format!("**label {i}**: 1 open\n\n")).collect::<String>();
+        let message = fit_message(&text);
+        assert!(message.chars().count() <= CONTENT_LENGTH);
+        let kept = message.lines().filter(|l| l.starts_with("**label")).count();
+        assert!(message.ends_with(&format!("\n\n… and {} more", 200 - kept)));
+        assert_eq!(fit_message("**label**: 1 open\n"), "**label**: 1 open\n");
+    }
+
+    #[test]
+    fn too_many_fields_are_split() {
+        let embeds = embed_with_fields(30, 10).split();
+        assert_eq!(embeds.len(), 2);
+        assert_eq!(embeds[0].fields.len(), FIELDS_PER_EMBED);
+        assert_eq!(embeds[1].fields.len(), 5);
+        assert_eq!(embeds[1].title, "Title (continued)");
+    }
+
+    #[test]
+    fn messages_respect_limits() {
+        let embeds = (0..12).map(|_| embed_with_fields(3, 600)).collect::<Vec<Embed>>();
+        let messages = paginate(embeds);
+        assert_eq!(messages.iter().map(|m| m.len()).sum::<usize>(), 12);
+        for message in &messages {
+            assert!(message.len() <= EMBEDS_PER_MESSAGE);
+            assert!(message.iter().map(|e| e.len()).sum::<usize>() <= MESSAGE_LENGTH);
+        }
+        assert_eq!(messages.len(), 4);
+    }
+
+    #[test]
+    fn small_embeds_share_a_message() {
+        let embeds = (0..4).map(|_| embed_with_fields(2, 20)).collect::<Vec<Embed>>();
+        assert_eq!(paginate(embeds).len(), 1);
+    }
+}
@@ -0,0 +1,100 @@
+use std::{fmt, future::Future, time::Duration};
+
+use crate::github::rate_limit::{Bucket, RateLimiter};
+
+static MAX_ATTEMPTS: u32 = 4;
+static FIRST_BACKOFF: Duration = Duration::from_secs(2);
+static FORBIDDEN: u16 = 403;
+static NOT_FOUND: u16 = 404;
+static TOO_MANY_REQUESTS: u16 = 429;
+
+#[derive(Debug)]
+pub enum FetchError {
+    /// A request failed, after retrying it if the failure looked transient.
+    Github(String, Box<octocrab::Error>),
+    /// A GraphQL query was answered with errors, or with data of an unexpected shape.
+    Graphql(String, String),
+    /// A recorded response could not be read.
+    Fixture(String, String),
+    Database(rusqlite::Error),
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Github(request, why) => write!(f, "could not fetch {request}: {}", describe(why)),
+            Self::Graphql(request, why) => write!(f, "could not fetch {request}: {why}"),
+            Self::Fixture(path, why) => write!(f, "could not read {path}: {why}"),
+            Self::Database(why) => write!(f, "database error: {why}"),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+impl FetchError {
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, Self::Github(_, why) if matches!(why.as_ref(), octocrab::Error::GitHub { source, .. } if source.status_code.as_u16() == NOT_FOUND))
+    }
+}
+
+impl From<rusqlite::Error> for FetchError {
+    fn from(why: rusqlite::Error) -> Self {
+        Self::Database(why)
+    }
+}
+
+/// Server errors, rate limiting and connection problems are worth retrying, anything else will fail again.
+fn is_transient(error: &octocrab::Error) -> bool {
+    match error {
+        octocrab::Error::GitHub { source, .. } => source.status_code.is_server_error() || is_rate_limited(error),
+        octocrab::Error::Http { .. } | octocrab::Error::Hyper { .. } | octocrab::Error::Service { .. } => true,
+        _ => false,
+    }
+}
+
+fn is_rate_limited(error: &octocrab::Error) -> bool {
+    match error {
+        octocrab::Error::GitHub { source, .. } => source.status_code.as_u16() == TOO_MANY_REQUESTS
+            || (source.status_code.as_u16() == FORBIDDEN && source.message.to_lowercase().contains("rate limit")),
+        _ => false,
+    }
+}
+
+/// The error without the backtrace octocrab appends to its messages.
+fn describe(error: &octocrab::Error) -> String {
+    match error {
+        octocrab::Error::GitHub { source, .. } => format!("GitHub returned {}: {}", source.status_code, source.message),
+        octocrab::Error::Http { source, .. } => source.to_string(),
+        octocrab::Error::Hyper { source, .. } => source.to_string(),
+        octocrab::Error::Service { source, .. } => source.to_string(),
+        octocrab::Error::Serde { source, .. } => format!("unexpected response: {source}"),
+        octocrab::Error::Json { source, .. } => format!("unexpected response at {}: {}", source.path(), source.inner()),
+        error => error.to_string(),
+    }
+}
+
+/// Sends the request built by `send` once `bucket` allows it, retrying transient failures with an exponential backoff.
+pub async fn retry<T, F, R>(limiter: &RateLimiter, bucket: Bucket, request: &str, mut send: F) -> Result<T, FetchError>
+where
+    F: FnMut() -> R,
+    R: Future<Output = octocrab::Result<T>>,
+{
+    let mut backoff = FIRST_BACKOFF;
+    for attempt in 1.. {
+        limiter.acquire(bucket).await;
+        match send().await {
+            Ok(response) => return Ok(response),
+            Err(why) if attempt < MAX_ATTEMPTS && is_transient(&why) => {
+                eprintln!("Error fetching {request} (attempt {attempt}/{MAX_ATTEMPTS}), retrying in {}s: {}", backoff.as_secs(), describe(&why));
+                tokio::time::sleep(backoff).await;
+                if is_rate_limited(&why) {
+                    limiter.refresh().await;
+                }
+                backoff *= 2;
+            },
+            Err(why) => return Err(FetchError::Github(request.to_string(), Box::new(why))),
+        }
+    }
+    unreachable!()
+}
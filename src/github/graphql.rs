@@ -0,0 +1,360 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use octocrab::models::IssueState;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::github::{error::{self, FetchError}, pr_state, rate_limit::{Bucket, RateLimiter}, source::{GithubSource, LatestRelease, ListQueries, ListQuery, Lists}, ParsedIssue, ParsedPR, ParsedReview, Repository};
+
+static PAGE_SIZE: usize = 100;
+
+static ISSUE_FIELDS: &str = "number url title state author { login } createdAt updatedAt closedAt labels(first: 20) { nodes { name } }";
+/// The state of pull requests is another enum, which cannot share the `state` name with issues in search results.
+/// Reviews past the first 100 are left out, which only undercounts the reviews of the busiest pull requests.
+static PULL_REQUEST_FIELDS: &str = "number url title pullRequestState: state author { login } createdAt updatedAt closedAt labels(first: 20) { nodes { name } } isDraft mergedAt \
+    reviewRequests(first: 20) { nodes { requestedReviewer { ... on User { login } ... on Team { combinedSlug } } } } \
+    reviews(first: 100) { nodes { author { login } state submittedAt } }";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Node {
+    number: u64,
+    url: String,
+    title: String,
+    #[serde(alias = "pullRequestState")]
+    state: String,
+    /// Missing when the account was deleted.
+    author: Option<Author>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    closed_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    is_draft: bool,
+    merged_at: Option<DateTime<Utc>>,
+    labels: Labels,
+    #[serde(default)]
+    review_requests: ReviewRequests,
+    #[serde(default)]
+    reviews: Reviews,
+}
+
+#[derive(Debug, Deserialize)]
+struct Author {
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Labels {
+    nodes: Vec<LabelName>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LabelName {
+    name: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ReviewRequests {
+    nodes: Vec<ReviewRequest>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReviewRequest {
+    /// Missing when the account was deleted, and without fields for bots and mannequins.
+    requested_reviewer: Option<Reviewer>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Reviewer {
+    login: Option<String>,
+    combined_slug: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Reviews {
+    nodes: Vec<Review>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Review {
+    author: Option<Author>,
+    state: String,
+    /// Missing while the review is pending.
+    submitted_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PageInfo {
+    has_next_page: bool,
+    end_cursor: Option<String>,
+}
+
+/// One page of a connection, nodes are parsed one by one so that a malformed one can be skipped.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Page {
+    nodes: Vec<Value>,
+    page_info: Option<PageInfo>,
+}
+
+impl Node {
+    fn user(&self) -> String {
+        self.author.as_ref().map_or_else(|| String::from("ghost"), |a| a.login.clone())
+    }
+
+    fn labels(&self) -> Vec<String> {
+        self.labels.nodes.iter().map(|l| l.name.clone()).collect()
+    }
+
+    fn into_issue(self) -> ParsedIssue {
+        ParsedIssue {
+            user: self.user(),
+            id: self.number,
+            state: if self.state == "OPEN" { IssueState::Open } else { IssueState::Closed },
+            labels: self.labels(),
+            url: self.url,
+            title: self.title,
+            creation_date: self.created_at,
+            updated_date: self.updated_at,
+            closed_date: self.closed_at,
+        }
+    }
+
+    fn into_pr(mut self) -> ParsedPR {
+        let reviews = std::mem::take(&mut self.reviews.nodes).into_iter().filter_map(Review::parse).collect();
+        ParsedPR {
+            user: self.user(),
+            id: self.number,
+            state: pr_state(self.state == "OPEN", self.is_draft, self.merged_at.is_some()),
+            open_state: if self.state == "OPEN" { IssueState::Open } else { IssueState::Closed },
+            labels: self.labels(),
+            requested_reviewers: self.review_requests.nodes.into_iter()
+                .filter_map(|r| r.requested_reviewer.and_then(|r| r.login.or(r.combined_slug)))
+                .collect(),
+            reviews: Vec::new(),
+            url: self.url,
+            title: self.title,
+            creation_date: self.created_at,
+            updated_date: self.updated_at,
+            closed_date: self.closed_at,
+        }.with_reviews(reviews)
+    }
+}
+
+impl Review {
+    /// Submitted reviews only, pending ones have no verdict yet.
+    fn parse(self) -> Option<ParsedReview> {
+        Some(ParsedReview {
+            reviewer: self.author.map_or_else(|| String::from("ghost"), |a| a.login),
+            state: self.state.to_lowercase().parse().ok()?,
+            submitted_date: self.submitted_at?,
+        })
+    }
+}
+
+/// Sends `query` and returns its `data`, GraphQL reporting errors in a successful response.
+async fn query(limiter: &RateLimiter, request: &str, query: &str, variables: Value) -> Result<Value, FetchError> {
+    let payload = json!({ "query": query, "variables": variables });
+    let octocrab = octocrab::instance();
+    let mut response: Value = error::retry(limiter, Bucket::Graphql, request, || octocrab.graphql(&payload)).await?;
+    if let Some(errors) = response.get("errors").and_then(Value::as_array).filter(|e| !e.is_empty()) {
+        let messages = errors.iter().filter_map(|e| e["message"].as_str()).collect::<Vec<&str>>().join(", ");
+        return Err(FetchError::Graphql(request.to_string(), messages));
+    }
+    Ok(response["data"].take())
+}
+
+fn page(value: Value, request: &str) -> Result<Page, FetchError> {
+    serde_json::from_value(value).map_err(|why| FetchError::Graphql(request.to_string(), format!("unexpected response: {why}")))
+}
+
+/// Parses the nodes of `page`, skipping and reporting the malformed ones by url, or number in `context`.
+fn parse_nodes(context: &str, page: Page, warnings: &mut Vec<String>) -> Vec<Node> {
+    page.nodes.into_iter().filter_map(|node| {
+        let item = match (node["url"].as_str(), node["number"].as_u64()) {
+            (Some(url), _) => url.to_string(),
+            (None, Some(number)) => format!("{context}#{number}"),
+            (None, None) => format!("an item of {context}"),
+        };
+        match serde_json::from_value::<Node>(node) {
+            Ok(node) => Some(node),
+            Err(why) => {
+                eprintln!("Skipping {item}: {why}");
+                warnings.push(format!("Skipped {item}: {why}"));
+                None
+            },
+        }
+    }).collect()
+}
+
+/// Pages issues and pull requests in queries of 100, and gets the four lists in a single query.
+pub struct GraphqlSource {
+    limiter: Arc<RateLimiter>,
+}
+
+impl GraphqlSource {
+    pub fn new(limiter: Arc<RateLimiter>) -> Self {
+        Self { limiter }
+    }
+}
+
+#[async_trait]
+impl GithubSource for GraphqlSource {
+    async fn issues(&self, repository: &Repository, since: Option<DateTime<Utc>>, warnings: &mut Vec<String>) -> Result<Vec<ParsedIssue>, FetchError> {
+        let request = format!("issues of {repository}");
+        let issues_query = format!("query($owner: String!, $name: String!, $since: DateTime, $after: String) {{
+            repository(owner: $owner, name: $name) {{
+                issues(first: {PAGE_SIZE}, after: $after, filterBy: {{ since: $since }}, orderBy: {{ field: UPDATED_AT, direction: ASC }}) {{
+                    nodes {{ {ISSUE_FIELDS} }}
+                    pageInfo {{ hasNextPage endCursor }}
+                }}
+            }}
+        }}");
+        let mut updated_issues = Vec::new();
+        let mut after = None;
+        loop {
+            let mut data = query(&self.limiter, &request, &issues_query, json!({ "owner": repository.owner, "name": repository.name, "since": since, "after": after })).await?;
+            let issues = page(data["repository"]["issues"].take(), &request)?;
+            let page_info = issues.page_info.as_ref().filter(|p| p.has_next_page).and_then(|p| p.end_cursor.clone());
+            updated_issues.extend(parse_nodes(&repository.to_string(), issues, warnings).into_iter().map(Node::into_issue));
+            match page_info {
+                Some(cursor) => after = Some(cursor),
+                None => break,
+            }
+        }
+        Ok(updated_issues)
+    }
+
+    async fn pull_requests(&self, repository: &Repository, since: Option<DateTime<Utc>>, _reviews_since: Option<DateTime<Utc>>, warnings: &mut Vec<String>) -> Result<Vec<ParsedPR>, FetchError> {
+        // Pull requests cannot be filtered by update date, so page from the most recently updated and stop once past the last sync
+        let request = format!("pull requests of {repository}");
+        let pull_requests_query = format!("query($owner: String!, $name: String!, $after: String) {{
+            repository(owner: $owner, name: $name) {{
+                pullRequests(first: {PAGE_SIZE}, after: $after, orderBy: {{ field: UPDATED_AT, direction: DESC }}) {{
+                    nodes {{ {PULL_REQUEST_FIELDS} }}
+                    pageInfo {{ hasNextPage endCursor }}
+                }}
+            }}
+        }}");
+        let mut updated_pull_requests = Vec::new();
+        let mut after = None;
+        'pages: loop {
+            let mut data = query(&self.limiter, &request, &pull_requests_query, json!({ "owner": repository.owner, "name": repository.name, "after": after })).await?;
+            let pull_requests = page(data["repository"]["pullRequests"].take(), &request)?;
+            let page_info = pull_requests.page_info.as_ref().filter(|p| p.has_next_page).and_then(|p| p.end_cursor.clone());
+            for pr in parse_nodes(&repository.to_string(), pull_requests, warnings).into_iter().map(Node::into_pr) {
+                if since.is_some_and(|since| pr.updated_date < since) {
+                    break 'pages;
+                }
+                updated_pull_requests.push(pr);
+            }
+            match page_info {
+                Some(cursor) => after = Some(cursor),
+                None => break,
+            }
+        }
+        Ok(updated_pull_requests)
+    }
+
+    async fn lists(&self, queries: &ListQueries, warnings: &mut Vec<String>) -> Result<Lists, FetchError> {
+        let search = |alias: &str, list: &ListQuery| format!(
+            "{alias}: search(type: ISSUE, first: {}, query: {}) {{ nodes {{ ... on Issue {{ {ISSUE_FIELDS} }} ... on PullRequest {{ {PULL_REQUEST_FIELDS} }} }} }}",
+            list.count, json!(list.query),
+        );
+        let lists_query = format!("query {{ {} {} {} {} }}",
+            search("staleIssues", &queries.stale_issues),
+            search("stalePullRequests", &queries.stale_pull_requests),
+            search("recentIssues", &queries.recent_issues),
+            search("recentPullRequests", &queries.recent_pull_requests),
+        );
+
+        let request = String::from("stale and recent lists");
+        let mut data = query(&self.limiter, &request, &lists_query, json!({})).await?;
+        let mut list = |alias: &str| -> Result<Vec<Node>, FetchError> {
+            Ok(parse_nodes(alias, page(data[alias].take(), &request)?, warnings))
+        };
+        Ok(Lists {
+            stale_issues: list("staleIssues")?.into_iter().map(Node::into_issue).collect(),
+            stale_pull_requests: list("stalePullRequests")?.into_iter().map(Node::into_pr).collect(),
+            recent_issues: list("recentIssues")?.into_iter().map(Node::into_issue).collect(),
+            recent_pull_requests: list("recentPullRequests")?.into_iter().map(Node::into_pr).collect(),
+        })
+    }
+
+    async fn latest_release(&self, repository: &Repository) -> Result<Option<LatestRelease>, FetchError> {
+        let request = format!("latest release of {repository}");
+        let release_query = "query($owner: String!, $name: String!) {
+            repository(owner: $owner, name: $name) { latestRelease { tag: tagName published: publishedAt } }
+        }";
+        let mut data = query(&self.limiter, &request, release_query, json!({ "owner": repository.owner, "name": repository.name })).await?;
+        serde_json::from_value::<Option<LatestRelease>>(data["repository"]["latestRelease"].take())
+            .map_err(|why| FetchError::Graphql(request, why.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::github::{graphql::*, PRState, ReviewState};
+
+    fn node(state: &str, draft: bool, merged: bool) -> Value {
+        json!({
+            "number": 5,
+            "url": "https://github.com/octo/cat/pull/5",
+            "title": "Fix",
+            "pullRequestState": state,
+            "author": null,
+            "createdAt": "2026-03-01T12:00:00Z",
+            "updatedAt": "2026-03-02T12:00:00Z",
+            "closedAt": if state == "OPEN" { Value::Null } else { json!("2026-03-02T12:00:00Z") },
+            "isDraft": draft,
+            "mergedAt": if merged { json!("2026-03-02T12:00:00Z") } else { Value::Null },
+            "labels": { "nodes": [{ "name": "bug" }] },
+            "reviewRequests": { "nodes": [{ "requestedReviewer": { "login": "mew" } }, { "requestedReviewer": { "combinedSlug": "octo/reviewers" } }, { "requestedReviewer": null }] },
+            "reviews": { "nodes": [
+                { "author": { "login": "celebi" }, "state": "CHANGES_REQUESTED", "submittedAt": "2026-03-01T18:00:00Z" },
+                { "author": null, "state": "PENDING", "submittedAt": null },
+            ] },
+        })
+    }
+
+    #[test]
+    fn nodes_become_the_rest_models() {
+        let pr = serde_json::from_value::<Node>(node("MERGED", false, true)).unwrap().into_pr();
+        assert_eq!(pr.state, PRState::Merged);
+        assert_eq!(pr.open_state, IssueState::Closed);
+        assert_eq!(pr.user, "ghost");
+        assert_eq!(pr.labels, vec!["bug"]);
+        assert_eq!(pr.requested_reviewers, vec!["mew", "octo/reviewers"]);
+        assert_eq!(pr.reviews.iter().map(|r| (r.reviewer.as_str(), r.state)).collect::<Vec<_>>(), vec![("celebi", ReviewState::ChangesRequested)]);
+        assert_eq!(serde_json::from_value::<Node>(node("OPEN", true, false)).unwrap().into_pr().state, PRState::Draft);
+        assert_eq!(serde_json::from_value::<Node>(node("CLOSED", false, false)).unwrap().into_pr().state, PRState::Closed);
+        assert_eq!(serde_json::from_value::<Node>(node("CLOSED", true, false)).unwrap().into_pr().state, PRState::Closed);
+        // Changes were requested by celebi
+        assert_eq!(serde_json::from_value::<Node>(node("OPEN", false, false)).unwrap().into_pr().state, PRState::ChangesRequested);
+
+        let mut issue = node("OPEN", false, false);
+        issue.as_object_mut().unwrap().retain(|key, _| key != "isDraft" && key != "mergedAt" && key != "pullRequestState");
+        issue["state"] = json!("OPEN");
+        let issue = serde_json::from_value::<Node>(issue).unwrap().into_issue();
+        assert_eq!(issue.state, IssueState::Open);
+        assert_eq!(issue.closed_date, None);
+    }
+
+    #[test]
+    fn malformed_nodes_are_skipped() {
+        let mut warnings = Vec::new();
+        let page = Page { nodes: vec![node("OPEN", false, false), json!({ "number": 6 })], page_info: None };
+        let nodes = parse_nodes("octo/cat", page, &mut warnings);
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("Skipped octo/cat#6"));
+    }
+}
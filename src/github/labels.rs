@@ -0,0 +1,240 @@
+use std::{collections::BTreeSet, fmt, ops::AddAssign, str::FromStr};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::github::{latency::Latency, window::Span, ParsedIssue};
+
+/// A label of the breakdown, configured by its name, or every label starting with a prefix such as "category:*".
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum LabelPattern {
+    Exact(String),
+    Prefix(String),
+}
+
+impl LabelPattern {
+    pub fn matches(&self, label: &str) -> bool {
+        match self {
+            Self::Exact(name) => label == name,
+            Self::Prefix(prefix) => label.starts_with(prefix.as_str()),
+        }
+    }
+}
+
+impl fmt::Display for LabelPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Exact(name) => write!(f, "{name}"),
+            Self::Prefix(prefix) => write!(f, "{prefix}*"),
+        }
+    }
+}
+
+impl FromStr for LabelPattern {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_suffix('*') {
+            _ if s.trim().is_empty() => Err(String::from("label patterns must not be empty")),
+            Some(prefix) => Ok(Self::Prefix(prefix.to_string())),
+            None => Ok(Self::Exact(s.to_string())),
+        }
+    }
+}
+
+impl TryFrom<String> for LabelPattern {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<LabelPattern> for String {
+    fn from(pattern: LabelPattern) -> Self {
+        pattern.to_string()
+    }
+}
+
+/// A category of issues, matched by their labels. Issues fall in the first configured category they match.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Category {
+    pub name: String,
+    /// Issues need at least one of these labels, unless there are none.
+    #[serde(default)]
+    pub any: Vec<LabelPattern>,
+    /// Issues need every one of these labels.
+    #[serde(default)]
+    pub all: Vec<LabelPattern>,
+    /// Issues must have none of these labels.
+    #[serde(default)]
+    pub none: Vec<LabelPattern>,
+}
+
+impl Category {
+    /// Category of the issues with `label`.
+    pub fn labelled(name: &str, label: &str) -> Self {
+        Self { name: name.to_string(), any: vec![LabelPattern::Exact(label.to_string())], all: Vec::new(), none: Vec::new() }
+    }
+
+    pub fn matches(&self, labels: &[String]) -> bool {
+        let has = |pattern: &LabelPattern| labels.iter().any(|l| pattern.matches(l));
+        (self.any.is_empty() || self.any.iter().any(has)) && self.all.iter().all(has) && !self.none.iter().any(has)
+    }
+
+    /// Search qualifiers of the issues matching the category on its own, regardless of the categories before it.
+    /// `None` with prefixes, which GitHub cannot search.
+    pub fn query(&self) -> Option<String> {
+        let name = |pattern: &LabelPattern| match pattern {
+            LabelPattern::Exact(name) => Some(format!("\"{name}\"")),
+            LabelPattern::Prefix(_) => None,
+        };
+        let mut qualifiers = Vec::new();
+        if !self.any.is_empty() {
+            qualifiers.push(format!("label:{}", self.any.iter().map(name).collect::<Option<Vec<String>>>()?.join(",")));
+        }
+        for label in &self.all {
+            qualifiers.push(format!("label:{}", name(label)?));
+        }
+        for label in &self.none {
+            qualifiers.push(format!("-label:{}", name(label)?));
+        }
+        Some(qualifiers.join(" "))
+    }
+}
+
+/// Index of the first of `categories` matching `labels`, `None` if the issue is uncategorised.
+pub fn categorise(categories: &[Category], labels: &[String]) -> Option<usize> {
+    categories.iter().position(|c| c.matches(labels))
+}
+
+/// Issues of a label: open at the report date, opened and closed in each window.
+#[derive(Clone, Debug, Default)]
+pub struct LabelStats {
+    pub label: String,
+    pub open: usize,
+    /// Issues opened with the label within each configured window, in order.
+    pub opened: Vec<usize>,
+    /// Issues with the label closed within each configured window, in order.
+    pub closed: Vec<usize>,
+    /// Ages of the open issues, sorted.
+    ages: Vec<TimeDelta>,
+}
+
+impl AddAssign<&LabelStats> for LabelStats {
+    fn add_assign(&mut self, other: &LabelStats) {
+        self.open += other.open;
+        self.opened.iter_mut().zip(&other.opened).for_each(|(count, other)| *count += other);
+        self.closed.iter_mut().zip(&other.closed).for_each(|(count, other)| *count += other);
+        self.ages.extend(other.ages.iter().copied());
+        self.ages.sort();
+    }
+}
+
+impl LabelStats {
+    /// Stats of every label matching `patterns` as of `now`, in the order of the patterns and then by name. Prefixes
+    /// only cover the labels some issue has, while exact labels are always counted.
+    pub fn count(issues: &[ParsedIssue], patterns: &[LabelPattern], spans: &[Span], now: DateTime<Utc>) -> Vec<Self> {
+        let mut stats: Vec<Self> = Vec::new();
+        for pattern in patterns {
+            let labels: BTreeSet<&str> = match pattern {
+                LabelPattern::Exact(name) => BTreeSet::from([name.as_str()]),
+                LabelPattern::Prefix(_) => issues.iter().flat_map(|i| &i.labels).map(String::as_str).filter(|l| pattern.matches(l)).collect(),
+            };
+            for label in labels {
+                if !stats.iter().any(|s| s.label == label) {
+                    stats.push(Self::of_label(label, issues, spans, now));
+                }
+            }
+        }
+        stats
+    }
+
+    fn of_label(label: &str, issues: &[ParsedIssue], spans: &[Span], now: DateTime<Utc>) -> Self {
+        let issues: Vec<&ParsedIssue> = issues.iter().filter(|i| i.labels.iter().any(|l| l == label)).collect();
+        let mut ages: Vec<TimeDelta> = issues.iter().filter(|i| i.open_at(now)).map(|i| now - i.creation_date).collect();
+        ages.sort();
+        let within = |span: &Span, date: Option<DateTime<Utc>>| date.is_some_and(|d| d <= now && span.contains(d));
+        Self {
+            label: label.to_string(),
+            open: ages.len(),
+            opened: spans.iter().map(|span| issues.iter().filter(|i| within(span, Some(i.creation_date))).count()).collect(),
+            closed: spans.iter().map(|span| issues.iter().filter(|i| within(span, i.closed_date)).count()).collect(),
+            ages,
+        }
+    }
+
+    /// Median age of the open issues, `None` without any.
+    pub fn median_age(&self) -> Option<TimeDelta> {
+        Latency::of(&self.ages).map(|l| l.median)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use crate::github::{labels::*, test_support};
+
+    fn date(day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap()
+    }
+
+    fn issue(created: u32, closed: Option<u32>, labels: &[&str]) -> ParsedIssue {
+        ParsedIssue { labels: labels.iter().map(|l| l.to_string()).collect(), ..test_support::issue(date(created), closed.map(date)) }
+    }
+
+    #[test]
+    fn patterns_are_parsed_from_their_names() {
+        assert_eq!("bug".parse::<LabelPattern>(), Ok(LabelPattern::Exact(String::from("bug"))));
+        assert_eq!("type: *".parse::<LabelPattern>(), Ok(LabelPattern::Prefix(String::from("type: "))));
+        assert_eq!("type: *".parse::<LabelPattern>().unwrap().to_string(), "type: *");
+        assert!(" ".parse::<LabelPattern>().is_err());
+    }
+
+    #[test]
+    fn first_matching_category_wins() {
+        let pattern = |p: &str| p.parse::<LabelPattern>().unwrap();
+        let categories = [
+            Category { name: String::from("Confirmed Bugs"), any: Vec::new(), all: vec![pattern("bug"), pattern("status: confirmed")], none: vec![pattern("wontfix")] },
+            Category { name: String::from("Bugs"), any: vec![pattern("bug"), pattern("type: bug*")], all: Vec::new(), none: Vec::new() },
+        ];
+        let labels = |labels: &[&str]| labels.iter().map(|l| l.to_string()).collect::<Vec<String>>();
+        assert_eq!(categorise(&categories, &labels(&["status: confirmed", "bug"])), Some(0));
+        assert_eq!(categorise(&categories, &labels(&["status: confirmed", "bug", "wontfix"])), Some(1));
+        assert_eq!(categorise(&categories, &labels(&["type: bug/crash"])), Some(1));
+        assert_eq!(categorise(&categories, &labels(&["status: confirmed"])), None);
+        assert_eq!(categorise(&categories, &[]), None);
+
+        assert_eq!(categories[0].query().as_deref(), Some("label:\"bug\" label:\"status: confirmed\" -label:\"wontfix\""));
+        assert_eq!(categories[1].query(), None);
+        assert_eq!(Category::labelled("Questions", "question").query().as_deref(), Some("label:\"question\""));
+    }
+
+    #[test]
+    fn labels_are_counted_per_pattern() {
+        let issues = [
+            issue(1, None, &["type: bug", "bug"]),
+            issue(3, Some(6), &["type: bug"]),
+            issue(5, None, &["type: bug"]),
+            issue(7, None, &["type: docs"]),
+            // Opened after the report date
+            issue(9, None, &["type: idea"]),
+        ];
+        let spans = [Span { start: Some(date(5)), end: date(10) }, Span { start: None, end: date(10) }];
+        let patterns = [LabelPattern::Prefix(String::from("type: ")), LabelPattern::Exact(String::from("bug")), LabelPattern::Exact(String::from("wontfix"))];
+        let stats = LabelStats::count(&issues, &patterns, &spans, date(8));
+        assert_eq!(stats.iter().map(|s| s.label.as_str()).collect::<Vec<&str>>(), ["type: bug", "type: docs", "type: idea", "bug", "wontfix"]);
+        assert_eq!((stats[0].open, stats[0].opened.clone(), stats[0].closed.clone()), (2, vec![1, 3], vec![1, 1]));
+        // Open for 7 and 3 days
+        assert_eq!(stats[0].median_age(), Some(TimeDelta::days(5)));
+        assert_eq!((stats[2].open, stats[2].opened.clone(), stats[2].median_age()), (0, vec![0, 0], None));
+        assert_eq!(stats[4].opened, vec![0, 0]);
+
+        let mut total = stats[0].clone();
+        total += &stats[1];
+        assert_eq!((total.open, total.opened.clone(), total.median_age()), (3, vec![2, 4], Some(TimeDelta::days(3))));
+    }
+}
@@ -0,0 +1,90 @@
+use chrono::{DateTime, TimeDelta, Utc};
+
+use crate::github::window::Span;
+
+/// Summary of how long items took to be merged, closed or first reviewed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Latency {
+    pub count: usize,
+    pub median: TimeDelta,
+    pub mean: TimeDelta,
+    pub p75: TimeDelta,
+    pub p90: TimeDelta,
+}
+
+impl Latency {
+    /// Summary of `durations`, which must be sorted. Percentiles interpolate between the closest ranks.
+    pub fn of(durations: &[TimeDelta]) -> Option<Self> {
+        let count = durations.len();
+        let percentile = |p: f64| {
+            let rank = p * (count - 1) as f64;
+            let (low, high) = (durations[rank.floor() as usize], durations[rank.ceil() as usize]);
+            low + TimeDelta::seconds(((high - low).num_seconds() as f64 * rank.fract()).round() as i64)
+        };
+        (count > 0).then(|| Self {
+            count,
+            median: percentile(0.5),
+            mean: durations.iter().sum::<TimeDelta>() / count as i32,
+            p75: percentile(0.75),
+            p90: percentile(0.9),
+        })
+    }
+}
+
+/// How long each item took to be merged, closed or first reviewed, sorted by when that happened.
+#[derive(Clone, Debug, Default)]
+pub struct Resolutions(Vec<(DateTime<Utc>, TimeDelta)>);
+
+impl Resolutions {
+    /// Resolutions from `(opened, resolved)` pairs.
+    pub fn new(items: impl Iterator<Item = (DateTime<Utc>, DateTime<Utc>)>) -> Self {
+        let mut resolutions: Vec<(DateTime<Utc>, TimeDelta)> = items.map(|(opened, resolved)| (resolved, resolved - opened)).collect();
+        resolutions.sort();
+        Self(resolutions)
+    }
+
+    /// Sorted durations of the items resolved within `span` and up to `now`.
+    pub fn within(&self, span: Span, now: DateTime<Utc>) -> Vec<TimeDelta> {
+        let start = span.start.map_or(0, |start| self.0.partition_point(|(resolved, _)| *resolved < start));
+        let end = self.0.partition_point(|(resolved, _)| *resolved < span.end && *resolved <= now);
+        let mut durations: Vec<TimeDelta> = self.0[start..end.max(start)].iter().map(|(_, duration)| *duration).collect();
+        durations.sort();
+        durations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use crate::github::latency::*;
+
+    fn hours(hours: &[i64]) -> Vec<TimeDelta> {
+        hours.iter().map(|h| TimeDelta::hours(*h)).collect()
+    }
+
+    #[test]
+    fn percentiles_interpolate_between_ranks() {
+        let latency = Latency::of(&hours(&[1, 2, 3, 4, 10])).unwrap();
+        assert_eq!(latency.count, 5);
+        assert_eq!(latency.median, TimeDelta::hours(3));
+        assert_eq!(latency.mean, TimeDelta::hours(4));
+        assert_eq!(latency.p75, TimeDelta::hours(4));
+        assert_eq!(latency.p90, TimeDelta::minutes(7*60 + 36));
+
+        let latency = Latency::of(&hours(&[2, 4])).unwrap();
+        assert_eq!(latency.median, TimeDelta::hours(3));
+        assert_eq!(Latency::of(&hours(&[5])).unwrap().p90, TimeDelta::hours(5));
+        assert_eq!(Latency::of(&[]), None);
+    }
+
+    #[test]
+    fn resolutions_are_picked_by_resolution_date() {
+        let date = |day: u32| Utc.with_ymd_and_hms(2026, 3, day, 0, 0, 0).unwrap();
+        let resolutions = Resolutions::new([(date(1), date(5)), (date(2), date(3)), (date(1), date(9))].into_iter());
+        let span = Span { start: Some(date(3)), end: date(10) };
+        assert_eq!(resolutions.within(span, date(31)), vec![TimeDelta::days(1), TimeDelta::days(4), TimeDelta::days(8)]);
+        assert_eq!(resolutions.within(span, date(6)), vec![TimeDelta::days(1), TimeDelta::days(4)]);
+        assert_eq!(resolutions.within(Span { start: Some(date(4)), end: date(10) }, date(2)), Vec::<TimeDelta>::new());
+    }
+}
@@ -0,0 +1,192 @@
+use std::{fmt, sync::Mutex, time::Duration};
+
+use chrono::{DateTime, Utc};
+
+/// Core requests left untouched, so other users of the token are not starved.
+static CORE_RESERVE: usize = 50;
+static SEARCH_RESERVE: usize = 1;
+static GRAPHQL_RESERVE: usize = 50;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Bucket {
+    Core,
+    Search,
+    /// Counted in points, a query of 100 nodes costs one.
+    Graphql,
+}
+
+impl Bucket {
+    fn reserve(self) -> usize {
+        match self {
+            Self::Core => CORE_RESERVE,
+            Self::Search => SEARCH_RESERVE,
+            Self::Graphql => GRAPHQL_RESERVE,
+        }
+    }
+}
+
+impl fmt::Display for Bucket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Core => "core",
+            Self::Search => "search",
+            Self::Graphql => "graphql",
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Budget {
+    pub limit: usize,
+    pub remaining: usize,
+    pub reset: DateTime<Utc>,
+    /// Requests sent since the last call to `RateLimiter::refresh_interval`.
+    spent: usize,
+}
+
+impl Budget {
+    fn from_rate(rate: &octocrab::models::Rate, spent: usize) -> Self {
+        Self {
+            limit: rate.limit,
+            remaining: rate.remaining,
+            reset: DateTime::from_timestamp(rate.reset as i64, 0).unwrap_or_default(),
+            spent,
+        }
+    }
+
+    /// Time to wait before a request can be sent without dipping into the reserve.
+    fn wait(&self, reserve: usize, now: DateTime<Utc>) -> Option<Duration> {
+        match self.remaining <= reserve {
+            true => (self.reset - now).to_std().ok().map(|wait| wait + Duration::from_secs(1)),
+            false => None,
+        }
+    }
+
+    /// Smallest interval, at least `base`, at which runs spending as much as the last one fit in the budget until it resets.
+    fn stretch(&self, base: Duration, reserve: usize, now: DateTime<Utc>) -> Duration {
+        let until_reset = (self.reset - now).to_std().unwrap_or_default();
+        if self.spent == 0 || until_reset.is_zero() {
+            return base;
+        }
+        match self.remaining.saturating_sub(reserve) / self.spent {
+            0 => until_reset.max(base),
+            runs => (until_reset / runs as u32).max(base),
+        }
+    }
+}
+
+impl fmt::Display for Budget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{} left, resets at {}", self.remaining, self.limit, self.reset.format("%H:%M:%S UTC"))
+    }
+}
+
+/// Tracks the core, search and GraphQL rate limits, polling the rate limit endpoint (which is free) and
+/// counting the requests sent in between, to pause before a bucket runs out.
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    core: Mutex<Option<Budget>>,
+    search: Mutex<Option<Budget>>,
+    graphql: Mutex<Option<Budget>>,
+}
+
+impl RateLimiter {
+    fn bucket(&self, bucket: Bucket) -> &Mutex<Option<Budget>> {
+        match bucket {
+            Bucket::Core => &self.core,
+            Bucket::Search => &self.search,
+            Bucket::Graphql => &self.graphql,
+        }
+    }
+
+    pub fn budget(&self, bucket: Bucket) -> Option<Budget> {
+        *self.bucket(bucket).lock().unwrap()
+    }
+
+    /// Replaces the estimates with the numbers reported by GitHub, keeping them if the poll fails.
+    pub async fn refresh(&self) {
+        match octocrab::instance().ratelimit().get().await {
+            Ok(limits) => {
+                let rates = [(Bucket::Core, Some(&limits.resources.core)), (Bucket::Search, Some(&limits.resources.search)), (Bucket::Graphql, limits.resources.graphql.as_ref())];
+                for (bucket, rate) in rates.into_iter().filter_map(|(bucket, rate)| Some((bucket, rate?))) {
+                    let mut budget = self.bucket(bucket).lock().unwrap();
+                    *budget = Some(Budget::from_rate(rate, budget.map_or(0, |b| b.spent)));
+                }
+            },
+            Err(why) => eprintln!("Error reading the rate limit: {why}"),
+        }
+    }
+
+    /// Waits until a request can be sent from `bucket` and counts it.
+    pub async fn acquire(&self, bucket: Bucket) {
+        if self.budget(bucket).is_none() {
+            self.refresh().await;
+        }
+        loop {
+            let wait = match self.bucket(bucket).lock().unwrap().as_mut() {
+                Some(budget) => match budget.wait(bucket.reserve(), Utc::now()) {
+                    Some(wait) => wait,
+                    None => {
+                        budget.remaining = budget.remaining.saturating_sub(1);
+                        budget.spent += 1;
+                        return;
+                    },
+                },
+                // The rate limit is unknown, let GitHub answer
+                None => return,
+            };
+            println!("The {bucket} rate limit is almost exhausted, pausing for {}s", wait.as_secs());
+            tokio::time::sleep(wait).await;
+            self.refresh().await;
+        }
+    }
+
+    /// Interval until the next refresh, `base` stretched so that refreshes spending as much as
+    /// the last one do not run out of either bucket. Starts counting the next run.
+    pub fn refresh_interval(&self, base: Duration) -> Duration {
+        let now = Utc::now();
+        [Bucket::Core, Bucket::Search, Bucket::Graphql].into_iter()
+            .filter_map(|bucket| {
+                let mut budget = self.bucket(bucket).lock().unwrap();
+                let budget = budget.as_mut()?;
+                let interval = budget.stretch(base, bucket.reserve(), now);
+                budget.spent = 0;
+                Some(interval)
+            })
+            .max()
+            .unwrap_or(base)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use crate::github::rate_limit::*;
+
+    fn budget(remaining: usize, spent: usize) -> Budget {
+        Budget { limit: 5000, remaining, reset: Utc.with_ymd_and_hms(2026, 3, 1, 13, 0, 0).unwrap(), spent }
+    }
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn pauses_only_when_reserve_is_reached() {
+        assert_eq!(budget(51, 0).wait(50, now()), None);
+        assert_eq!(budget(50, 0).wait(50, now()), Some(Duration::from_secs(3601)));
+        // Past the reset, GitHub has refilled the bucket
+        assert_eq!(budget(0, 0).wait(50, now() + chrono::Duration::hours(2)), None);
+    }
+
+    #[test]
+    fn interval_is_stretched_to_last_until_reset() {
+        let base = Duration::from_secs(400);
+        assert_eq!(budget(4000, 100).stretch(base, 50, now()), base);
+        assert_eq!(budget(4000, 0).stretch(base, 50, now()), base);
+        // 4 more runs fit in the hour left
+        assert_eq!(budget(450, 100).stretch(base, 50, now()), Duration::from_secs(900));
+        assert_eq!(budget(100, 100).stretch(base, 50, now()), Duration::from_secs(3600));
+    }
+}
@@ -0,0 +1,81 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+use crate::github::{latency::Resolutions, window::Span, ParsedPR};
+
+/// Reviews of the pull requests by people other than their authors, up to an instant.
+#[derive(Clone, Debug, Default)]
+pub struct ReviewActivity {
+    /// How long each reviewed pull request waited for its first review.
+    first_reviews: Resolutions,
+    /// When each reviewer submitted their reviews, sorted.
+    submitted: BTreeMap<String, Vec<DateTime<Utc>>>,
+}
+
+impl ReviewActivity {
+    /// Activity up to `until`, ignoring any later review.
+    pub fn new(pull_requests: &[ParsedPR], until: DateTime<Utc>) -> Self {
+        let mut submitted: BTreeMap<String, Vec<DateTime<Utc>>> = BTreeMap::new();
+        for review in pull_requests.iter().flat_map(ParsedPR::peer_reviews).filter(|r| r.submitted_date <= until) {
+            submitted.entry(review.reviewer.clone()).or_default().push(review.submitted_date);
+        }
+        submitted.values_mut().for_each(|instants| instants.sort());
+        let first_reviews = Resolutions::new(pull_requests.iter().filter_map(|p| Some((p.creation_date, p.first_review()?))));
+        Self { first_reviews, submitted }
+    }
+
+    /// Sorted time to first review of the pull requests first reviewed within `span` and up to `now`.
+    pub fn time_to_first_review(&self, span: Span, now: DateTime<Utc>) -> Vec<TimeDelta> {
+        self.first_reviews.within(span, now)
+    }
+
+    /// Reviews each reviewer submitted within `span`, without the reviewers who submitted none.
+    pub fn within(&self, span: Span) -> BTreeMap<String, usize> {
+        self.submitted.iter()
+            .map(|(reviewer, instants)| {
+                let start = span.start.map_or(0, |start| instants.partition_point(|i| *i < start));
+                let end = instants.partition_point(|i| *i < span.end);
+                (reviewer.clone(), end.saturating_sub(start))
+            })
+            .filter(|(_, count)| *count > 0)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use crate::github::{reviews::*, test_support, PRState, ParsedReview, ReviewState};
+
+    fn date(day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap()
+    }
+
+    fn review(reviewer: &str, day: u32) -> ParsedReview {
+        ParsedReview { reviewer: reviewer.to_string(), state: ReviewState::Commented, submitted_date: date(day) }
+    }
+
+    fn pull_request(created: u32, reviews: Vec<ParsedReview>) -> ParsedPR {
+        ParsedPR { reviews, ..test_support::pull_request(date(created), None, PRState::Ready) }
+    }
+
+    #[test]
+    fn reviews_by_the_author_are_left_out() {
+        let pull_requests = [
+            pull_request(1, vec![review("octocat", 2), review("mew", 4), review("celebi", 5)]),
+            pull_request(3, vec![review("mew", 4), review("mew", 9)]),
+            pull_request(6, vec![review("octocat", 7)]),
+        ];
+        let activity = ReviewActivity::new(&pull_requests, date(8));
+        let span = Span { start: Some(date(4)), end: date(10) };
+        assert_eq!(activity.time_to_first_review(span, date(8)), vec![TimeDelta::days(1), TimeDelta::days(3)]);
+        // The review of the 9th came after the activity was built
+        assert_eq!(activity.within(span), BTreeMap::from([(String::from("celebi"), 1), (String::from("mew"), 2)]));
+        assert_eq!(activity.within(Span { start: Some(date(5)), end: date(10) }), BTreeMap::from([(String::from("celebi"), 1)]));
+        assert!(pull_requests[2].waiting_at(date(8)));
+        assert!(!pull_requests[0].waiting_at(date(8)));
+        assert!(pull_requests[0].waiting_at(date(3)));
+    }
+}
@@ -0,0 +1,174 @@
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use chrono_tz::Tz;
+
+use crate::github::{window::Span, PRState, ParsedIssue, ParsedPR};
+
+/// Something that happened to an issue or a pull request.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Event {
+    OpenedPr,
+    MergedPr,
+    CancelledPr,
+    /// Merged or closed without being merged.
+    ClosedPr,
+    OpenedIssue,
+    ClosedIssue,
+}
+
+impl Event {
+    const COUNT: usize = 6;
+}
+
+/// Instants of one kind of event, sorted, along with how many happened before each day.
+#[derive(Clone, Debug, Default)]
+struct Timeline {
+    instants: Vec<DateTime<Utc>>,
+    /// Events before each day from the first day of the series, and the total: the prefix sums of the daily counts.
+    before_day: Vec<usize>,
+}
+
+/// Events of the issues and pull requests bucketed by day in a timezone, built in a single pass. Ranges of whole days
+/// are answered from prefix sums, and the partial days at the bounds of a span by searching within them.
+#[derive(Clone, Debug)]
+pub struct DailySeries {
+    timezone: Tz,
+    first_day: NaiveDate,
+    timelines: [Timeline; Event::COUNT],
+}
+
+impl DailySeries {
+    /// Series of what happened up to `until`, ignoring anything later.
+    pub fn new(issues: &[ParsedIssue], pull_requests: &[ParsedPR], timezone: Tz, until: DateTime<Utc>) -> Self {
+        let mut instants: [Vec<DateTime<Utc>>; Event::COUNT] = Default::default();
+        let mut push = |event: Event, instant: DateTime<Utc>| {
+            if instant <= until {
+                instants[event as usize].push(instant);
+            }
+        };
+        for pull_request in pull_requests {
+            push(Event::OpenedPr, pull_request.creation_date);
+            if let Some(closed) = pull_request.closed_date {
+                push(Event::ClosedPr, closed);
+                match pull_request.state {
+                    PRState::Merged => push(Event::MergedPr, closed),
+                    PRState::Closed => push(Event::CancelledPr, closed),
+                    _ => {},
+                }
+            }
+        }
+        for issue in issues {
+            push(Event::OpenedIssue, issue.creation_date);
+            if let Some(closed) = issue.closed_date {
+                push(Event::ClosedIssue, closed);
+            }
+        }
+
+        let local_day = |instant: &DateTime<Utc>| instant.with_timezone(&timezone).date_naive();
+        let last_day = local_day(&until);
+        let first_day = instants.iter().filter_map(|i| i.iter().min()).map(local_day).min().unwrap_or(last_day).min(last_day);
+        let days = (last_day - first_day).num_days() as usize + 1;
+        let timelines = instants.map(|mut instants| {
+            instants.sort();
+            let mut before_day = Vec::with_capacity(days + 1);
+            let mut i = 0;
+            for day in first_day.iter_days().take(days + 1) {
+                while i < instants.len() && local_day(&instants[i]) < day {
+                    i += 1;
+                }
+                before_day.push(i);
+            }
+            Timeline { instants, before_day }
+        });
+        Self { timezone, first_day, timelines }
+    }
+
+    /// Events that happened within `span`.
+    pub fn count(&self, event: Event, span: Span) -> usize {
+        self.before(event, span.end) - span.start.map_or(0, |start| self.before(event, start))
+    }
+
+    /// Events of each day from `first` to `last`.
+    pub fn daily(&self, event: Event, first: NaiveDate, last: NaiveDate) -> Vec<usize> {
+        first.iter_days().take_while(|day| *day <= last)
+            .map(|day| self.before_day(event, day + Days::new(1)) - self.before_day(event, day))
+            .collect()
+    }
+
+    /// Events up to the end of `day`.
+    pub fn until_end_of(&self, event: Event, day: NaiveDate) -> usize {
+        self.before_day(event, day + Days::new(1))
+    }
+
+    /// Events before the start of `day`.
+    fn before_day(&self, event: Event, day: NaiveDate) -> usize {
+        let timeline = &self.timelines[event as usize];
+        match day.signed_duration_since(self.first_day).num_days() {
+            ..=0 => 0,
+            index => timeline.before_day.get(index as usize).copied().unwrap_or(timeline.instants.len()),
+        }
+    }
+
+    /// Events before `instant`.
+    fn before(&self, event: Event, instant: DateTime<Utc>) -> usize {
+        let day = instant.with_timezone(&self.timezone).date_naive();
+        let (start, end) = (self.before_day(event, day), self.before_day(event, day + Days::new(1)));
+        start + self.timelines[event as usize].instants[start..end].partition_point(|i| *i < instant)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeDelta, TimeZone};
+
+    use crate::github::{series::*, test_support::{issue, pull_request}};
+
+    fn date(day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, day, hour, 0, 0).unwrap()
+    }
+
+    fn series() -> DailySeries {
+        let issues = [
+            issue(date(1, 9), Some(date(3, 18))),
+            issue(date(3, 10), None),
+            issue(date(3, 23), Some(date(9, 12))),
+        ];
+        let pull_requests = [
+            pull_request(date(2, 9), Some(date(3, 12)), PRState::Merged),
+            pull_request(date(3, 9), Some(date(4, 9)), PRState::Closed),
+            pull_request(date(5, 9), Some(date(5, 10)), PRState::Closed),
+        ];
+        DailySeries::new(&issues, &pull_requests, Tz::UTC, date(8, 12))
+    }
+
+    fn day(day: u32) -> NaiveDate {
+        date(day, 0).date_naive()
+    }
+
+    #[test]
+    fn events_are_bucketed_by_day() {
+        let series = series();
+        assert_eq!(series.daily(Event::OpenedIssue, day(1), day(4)), vec![1, 0, 2, 0]);
+        assert_eq!(series.daily(Event::ClosedPr, day(2), day(6)), vec![0, 1, 1, 1, 0]);
+        assert_eq!(series.daily(Event::MergedPr, day(3), day(3)), vec![1]);
+        // Closed on the 9th, after the end of the series
+        assert_eq!(series.until_end_of(Event::ClosedIssue, day(20)), 1);
+        assert_eq!(series.until_end_of(Event::OpenedPr, day(1)), 0);
+    }
+
+    #[test]
+    fn spans_are_counted_to_the_instant() {
+        let series = series();
+        let span = |start: DateTime<Utc>, end: DateTime<Utc>| Span { start: Some(start), end };
+        assert_eq!(series.count(Event::OpenedIssue, span(date(3, 10), date(3, 23))), 1);
+        assert_eq!(series.count(Event::OpenedIssue, span(date(3, 10), date(3, 23) + TimeDelta::seconds(1))), 2);
+        assert_eq!(series.count(Event::OpenedPr, Span { start: None, end: date(31, 0) }), 3);
+        assert_eq!(series.count(Event::CancelledPr, span(date(1, 0), date(4, 9))), 0);
+    }
+
+    #[test]
+    fn days_follow_the_timezone() {
+        let issues = [issue(date(3, 23), None)];
+        let series = DailySeries::new(&issues, &[], chrono_tz::Europe::Paris, date(8, 12));
+        assert_eq!(series.daily(Event::OpenedIssue, day(3), day(4)), vec![0, 1]);
+    }
+}
@@ -0,0 +1,509 @@
+use std::{collections::HashMap, fs, path::{Path, PathBuf}, sync::Arc};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use octocrab::models::{self, issues::Issue, IssueState, pulls::{PullRequest, Review}, repos::Release};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{config::Config, github::{error::{self, FetchError}, parse_issue, parse_pr, parse_pr_from_issue, parse_review, rate_limit::{Bucket, RateLimiter}, ParsedIssue, ParsedPR, Repository}};
+
+/// A search of the report, and the number of results to keep.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ListQuery {
+    pub query: String,
+    pub count: u8,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ListQueries {
+    pub stale_issues: ListQuery,
+    pub stale_pull_requests: ListQuery,
+    pub recent_issues: ListQuery,
+    pub recent_pull_requests: ListQuery,
+}
+
+impl ListQueries {
+    fn all(&self) -> [&ListQuery; 4] {
+        [&self.stale_issues, &self.stale_pull_requests, &self.recent_issues, &self.recent_pull_requests]
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Lists {
+    pub stale_issues: Vec<ParsedIssue>,
+    pub stale_pull_requests: Vec<ParsedPR>,
+    pub recent_issues: Vec<ParsedIssue>,
+    pub recent_pull_requests: Vec<ParsedPR>,
+}
+
+impl Lists {
+    /// Lists from REST search results, keyed by query.
+    fn from_searches(queries: &ListQueries, searches: &HashMap<String, Vec<SearchItem>>) -> Self {
+        let search = |list: &ListQuery| searches.get(&list.query).into_iter().flatten().take(list.count as usize).cloned();
+        Self {
+            stale_issues: search(&queries.stale_issues).map(|i| parse_issue(i.issue)).collect(),
+            stale_pull_requests: search(&queries.stale_pull_requests).map(SearchItem::into_pr).collect(),
+            recent_issues: search(&queries.recent_issues).map(|i| parse_issue(i.issue)).collect(),
+            recent_pull_requests: search(&queries.recent_pull_requests).map(SearchItem::into_pr).collect(),
+        }
+    }
+}
+
+/// A REST search result, with the fields of pull requests that the issue model leaves out.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct SearchItem {
+    #[serde(flatten)]
+    issue: Issue,
+    #[serde(default)]
+    draft: bool,
+    pull_request: Option<SearchPullRequest>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct SearchPullRequest {
+    merged_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResults {
+    items: Vec<SearchItem>,
+}
+
+impl SearchItem {
+    fn into_pr(self) -> ParsedPR {
+        let merged = self.pull_request.is_some_and(|pr| pr.merged_at.is_some());
+        parse_pr_from_issue(self.issue, self.draft, merged)
+    }
+}
+
+/// The latest published release of a repository, which starts the "since release" window.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct LatestRelease {
+    pub tag: String,
+    pub published: DateTime<Utc>,
+}
+
+impl LatestRelease {
+    fn from_release(release: Release) -> Option<Self> {
+        Some(Self { published: release.published_at?, tag: release.tag_name })
+    }
+}
+
+/// Where the issues and pull requests come from. Malformed items are skipped and described in `warnings`.
+#[async_trait]
+pub trait GithubSource: Send + Sync {
+    /// Issues of `repository` updated since `since`, without pull requests.
+    async fn issues(&self, repository: &Repository, since: Option<DateTime<Utc>>, warnings: &mut Vec<String>) -> Result<Vec<ParsedIssue>, FetchError>;
+
+    /// Pull requests of `repository` updated since `since`, with their reviews. The closed pull requests last
+    /// updated before `reviews_since` may come without them.
+    async fn pull_requests(&self, repository: &Repository, since: Option<DateTime<Utc>>, reviews_since: Option<DateTime<Utc>>, warnings: &mut Vec<String>) -> Result<Vec<ParsedPR>, FetchError>;
+
+    /// Results of the stale and recent searches.
+    async fn lists(&self, queries: &ListQueries, warnings: &mut Vec<String>) -> Result<Lists, FetchError>;
+
+    /// Latest published release of `repository`, `None` if it has none.
+    async fn latest_release(&self, repository: &Repository) -> Result<Option<LatestRelease>, FetchError>;
+}
+
+/// Pages the REST issues and pulls lists, along with the reviews of each updated pull request, and runs one REST search per list.
+pub struct RestSource {
+    limiter: Arc<RateLimiter>,
+    recorder: Option<Recorder>,
+}
+
+impl RestSource {
+    pub fn new(limiter: Arc<RateLimiter>) -> Self {
+        Self { limiter, recorder: None }
+    }
+
+    /// Records every response in the run directory of `recorder`. Recording fetches everything
+    /// regardless of `since`, so that the run can be replayed without the database.
+    pub fn recording(mut self, recorder: Recorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    async fn raw_issues(&self, repository: &Repository, since: Option<DateTime<Utc>>) -> Result<Vec<Issue>, FetchError> {
+        let octocrab = octocrab::instance();
+        let request = format!("issues of {repository}");
+        let issue_handler = octocrab.issues(&repository.owner, &repository.name);
+        let mut issues_page = error::retry(&self.limiter, Bucket::Core, &request, || {
+            let mut builder = issue_handler
+                .list()
+                .state(octocrab::params::State::All)
+                .sort(octocrab::params::issues::Sort::Updated)
+                .direction(octocrab::params::Direction::Ascending)
+                .per_page(100);
+            if let Some(since) = since {
+                builder = builder.since(since);
+            }
+            builder.send()
+        }).await?;
+        let mut issues = Vec::new();
+        loop {
+            issues.append(&mut issues_page.items);
+            issues_page = match error::retry(&self.limiter, Bucket::Core, &request, || octocrab.get_page::<models::issues::Issue>(&issues_page.next)).await? {
+                Some(next_page) => next_page,
+                None => break,
+            }
+        }
+        Ok(issues)
+    }
+
+    async fn raw_pull_requests(&self, repository: &Repository, since: Option<DateTime<Utc>>) -> Result<Vec<PullRequest>, FetchError> {
+        // The pulls endpoint has no `since`, so page from the most recently updated and stop once past the last sync
+        let octocrab = octocrab::instance();
+        let request = format!("pull requests of {repository}");
+        let pulls_handler = octocrab.pulls(&repository.owner, &repository.name);
+        let mut pull_requests_page = error::retry(&self.limiter, Bucket::Core, &request, || pulls_handler
+            .list()
+            .state(octocrab::params::State::All)
+            .sort(octocrab::params::pulls::Sort::Updated)
+            .direction(octocrab::params::Direction::Descending)
+            .per_page(100)
+            .send()
+        ).await?;
+        let mut pull_requests = Vec::new();
+        loop {
+            let past_since = pull_requests_page.items.iter().any(|pr| since.zip(pr.updated_at).is_some_and(|(since, updated)| updated < since));
+            pull_requests.append(&mut pull_requests_page.items);
+            if past_since {
+                break;
+            }
+            pull_requests_page = match error::retry(&self.limiter, Bucket::Core, &request, || octocrab.get_page::<models::pulls::PullRequest>(&pull_requests_page.next)).await? {
+                Some(next_page) => next_page,
+                None => break,
+            }
+        }
+        Ok(pull_requests)
+    }
+
+    async fn raw_reviews(&self, repository: &Repository, numbers: impl Iterator<Item = u64>) -> Result<HashMap<u64, Vec<Review>>, FetchError> {
+        let octocrab = octocrab::instance();
+        let pulls_handler = octocrab.pulls(&repository.owner, &repository.name);
+        let mut reviews = HashMap::new();
+        for number in numbers {
+            let request = format!("reviews of {repository}#{number}");
+            let mut reviews_page = error::retry(&self.limiter, Bucket::Core, &request, || pulls_handler.list_reviews(number).per_page(100).send()).await?;
+            let mut pull_request_reviews = Vec::new();
+            loop {
+                pull_request_reviews.append(&mut reviews_page.items);
+                reviews_page = match error::retry(&self.limiter, Bucket::Core, &request, || octocrab.get_page::<Review>(&reviews_page.next)).await? {
+                    Some(next_page) => next_page,
+                    None => break,
+                }
+            }
+            reviews.insert(number, pull_request_reviews);
+        }
+        Ok(reviews)
+    }
+
+    async fn raw_search(&self, list: &ListQuery) -> Result<Vec<SearchItem>, FetchError> {
+        // The search of octocrab returns the issue model, without the draft flag and merge date of pull requests
+        let octocrab = octocrab::instance();
+        let parameters = [("q", list.query.clone()), ("per_page", list.count.to_string())];
+        let results: SearchResults = error::retry(&self.limiter, Bucket::Search, &format!("search \"{}\"", list.query), || {
+            octocrab.get("/search/issues", Some(&parameters))
+        }).await?;
+        Ok(results.items)
+    }
+
+    async fn raw_latest_release(&self, repository: &Repository) -> Result<Option<Release>, FetchError> {
+        let octocrab = octocrab::instance();
+        let repository_handler = octocrab.repos(&repository.owner, &repository.name);
+        let releases = repository_handler.releases();
+        match error::retry(&self.limiter, Bucket::Core, &format!("latest release of {repository}"), || releases.get_latest()).await {
+            Ok(release) => Ok(Some(release)),
+            Err(why) if why.is_not_found() => Ok(None),
+            Err(why) => Err(why),
+        }
+    }
+}
+
+#[async_trait]
+impl GithubSource for RestSource {
+    async fn issues(&self, repository: &Repository, since: Option<DateTime<Utc>>, _warnings: &mut Vec<String>) -> Result<Vec<ParsedIssue>, FetchError> {
+        let issues = match &self.recorder {
+            Some(recorder) => {
+                let issues = self.raw_issues(repository, None).await?;
+                recorder.save(&repository_path(repository, "issues.json"), &issues)?;
+                issues
+            },
+            None => self.raw_issues(repository, since).await?,
+        };
+        Ok(updated_issues(issues, since))
+    }
+
+    async fn pull_requests(&self, repository: &Repository, since: Option<DateTime<Utc>>, reviews_since: Option<DateTime<Utc>>, warnings: &mut Vec<String>) -> Result<Vec<ParsedPR>, FetchError> {
+        let (pull_requests, reviews) = match &self.recorder {
+            Some(recorder) => {
+                let pull_requests = self.raw_pull_requests(repository, None).await?;
+                let reviews = self.raw_reviews(repository, pull_requests.iter().map(|p| p.number)).await?;
+                recorder.save(&repository_path(repository, "pulls.json"), &pull_requests)?;
+                recorder.save(&repository_path(repository, "reviews.json"), &reviews)?;
+                (updated_pull_requests(pull_requests, repository, since, warnings), reviews)
+            },
+            None => {
+                // Only the pull requests open or updated since the last sync need their reviews fetched again
+                let pull_requests = updated_pull_requests(self.raw_pull_requests(repository, since).await?, repository, since, warnings);
+                let reviewed = pull_requests.iter()
+                    .filter(|p| p.open_state == IssueState::Open || reviews_since.is_none_or(|since| p.updated_date >= since))
+                    .map(|p| p.id);
+                let reviews = self.raw_reviews(repository, reviewed).await?;
+                (pull_requests, reviews)
+            },
+        };
+        Ok(attach_reviews(pull_requests, reviews))
+    }
+
+    async fn lists(&self, queries: &ListQueries, _warnings: &mut Vec<String>) -> Result<Lists, FetchError> {
+        let mut searches = HashMap::new();
+        for list in queries.all() {
+            searches.insert(list.query.clone(), self.raw_search(list).await?);
+        }
+        if let Some(recorder) = &self.recorder {
+            recorder.save_searches(&searches)?;
+        }
+        Ok(Lists::from_searches(queries, &searches))
+    }
+
+    async fn latest_release(&self, repository: &Repository) -> Result<Option<LatestRelease>, FetchError> {
+        let release = self.raw_latest_release(repository).await?;
+        if let Some(recorder) = &self.recorder {
+            recorder.save(&repository_path(repository, "release.json"), &release)?;
+        }
+        Ok(release.and_then(LatestRelease::from_release))
+    }
+}
+
+/// Writes the responses of a run to `{dir}/{run id}`, in the layout read by `FixtureSource`.
+#[derive(Clone, Debug)]
+pub struct Recorder {
+    dir: PathBuf,
+    run: String,
+}
+
+/// What `xatu replay` needs besides the responses: the configuration of the run and the fetch date of each report.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Run {
+    pub config: Config,
+    pub reports: Vec<(Repository, DateTime<Utc>)>,
+}
+
+impl Recorder {
+    pub fn new(root: impl AsRef<Path>, now: DateTime<Utc>) -> Self {
+        let run = now.format("%Y%m%dT%H%M%SZ").to_string();
+        Self { dir: root.as_ref().join(&run), run }
+    }
+
+    pub fn run(&self) -> &str {
+        &self.run
+    }
+
+    fn save(&self, path: &Path, value: &impl Serialize) -> Result<(), FetchError> {
+        let path = self.dir.join(path);
+        let fail = |why: String| FetchError::Fixture(path.display().to_string(), why);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|why| fail(why.to_string()))?;
+        }
+        let content = serde_json::to_string_pretty(value).map_err(|why| fail(why.to_string()))?;
+        fs::write(&path, content).map_err(|why| fail(why.to_string()))
+    }
+
+    /// Adds `searches` to those recorded earlier in the run, as the report of each repository runs its own.
+    fn save_searches(&self, searches: &HashMap<String, Vec<SearchItem>>) -> Result<(), FetchError> {
+        let mut recorded: HashMap<String, Vec<SearchItem>> = FixtureSource::new(&self.dir).load_or_default("search.json")?;
+        recorded.extend(searches.iter().map(|(query, items)| (query.clone(), items.clone())));
+        self.save(Path::new("search.json"), &recorded)
+    }
+
+    /// Records the date each report was fetched at, once the run is over.
+    pub fn finish(&self, run: &Run) -> Result<(), FetchError> {
+        self.save(Path::new("run.json"), run)
+    }
+}
+
+/// Recorded REST responses: `{owner}/{name}/issues.json` and `{owner}/{name}/pulls.json` hold every
+/// item of a repository, `{owner}/{name}/reviews.json` maps pull request numbers to their reviews,
+/// `{owner}/{name}/release.json` holds the latest release, the last two only if recorded, and `search.json`
+/// maps each search query to its results.
+pub struct FixtureSource {
+    dir: PathBuf,
+}
+
+impl FixtureSource {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn load<T: DeserializeOwned>(&self, path: impl AsRef<Path>) -> Result<T, FetchError> {
+        let path = self.dir.join(path);
+        let content = fs::read_to_string(&path).map_err(|why| FetchError::Fixture(path.display().to_string(), why.to_string()))?;
+        serde_json::from_str(&content).map_err(|why| FetchError::Fixture(path.display().to_string(), why.to_string()))
+    }
+
+    /// Loads `path` if it was recorded.
+    fn load_or_default<T: DeserializeOwned + Default>(&self, path: impl AsRef<Path>) -> Result<T, FetchError> {
+        match self.dir.join(&path).exists() {
+            true => self.load(path),
+            false => Ok(T::default()),
+        }
+    }
+}
+
+impl FixtureSource {
+    pub fn run(&self) -> Result<Run, FetchError> {
+        self.load("run.json")
+    }
+}
+
+#[async_trait]
+impl GithubSource for FixtureSource {
+    async fn issues(&self, repository: &Repository, since: Option<DateTime<Utc>>, _warnings: &mut Vec<String>) -> Result<Vec<ParsedIssue>, FetchError> {
+        let issues: Vec<Issue> = self.load(repository_path(repository, "issues.json"))?;
+        Ok(updated_issues(issues, since))
+    }
+
+    async fn pull_requests(&self, repository: &Repository, since: Option<DateTime<Utc>>, _reviews_since: Option<DateTime<Utc>>, warnings: &mut Vec<String>) -> Result<Vec<ParsedPR>, FetchError> {
+        let pull_requests: Vec<PullRequest> = self.load(repository_path(repository, "pulls.json"))?;
+        let reviews = self.load_or_default(repository_path(repository, "reviews.json"))?;
+        Ok(attach_reviews(updated_pull_requests(pull_requests, repository, since, warnings), reviews))
+    }
+
+    async fn lists(&self, queries: &ListQueries, _warnings: &mut Vec<String>) -> Result<Lists, FetchError> {
+        let searches: HashMap<String, Vec<SearchItem>> = self.load("search.json")?;
+        if let Some(missing) = queries.all().into_iter().find(|list| !searches.contains_key(&list.query)) {
+            return Err(FetchError::Fixture(self.dir.join("search.json").display().to_string(), format!("no results recorded for \"{}\"", missing.query)));
+        }
+        Ok(Lists::from_searches(queries, &searches))
+    }
+
+    async fn latest_release(&self, repository: &Repository) -> Result<Option<LatestRelease>, FetchError> {
+        let release: Option<Release> = self.load_or_default(repository_path(repository, "release.json"))?;
+        Ok(release.and_then(LatestRelease::from_release))
+    }
+}
+
+fn repository_path(repository: &Repository, file: &str) -> PathBuf {
+    Path::new(&repository.owner).join(&repository.name).join(file)
+}
+
+/// Issues updated since `since`, without the pull requests the issues list also returns, oldest first.
+fn updated_issues(mut issues: Vec<Issue>, since: Option<DateTime<Utc>>) -> Vec<ParsedIssue> {
+    issues.sort_by_key(|i| i.updated_at);
+    issues.into_iter()
+        .filter(|i| i.pull_request.is_none() && since.is_none_or(|since| i.updated_at >= since))
+        .map(parse_issue)
+        .collect()
+}
+
+fn updated_pull_requests(pull_requests: Vec<PullRequest>, repository: &Repository, since: Option<DateTime<Utc>>, warnings: &mut Vec<String>) -> Vec<ParsedPR> {
+    pull_requests.into_iter()
+        .filter_map(|pr| parse_or_skip(pr, repository, warnings))
+        .filter(|pr| since.is_none_or(|since| pr.updated_date >= since))
+        .collect()
+}
+
+/// Attaches its recorded or fetched reviews to each pull request, see `ParsedPR::with_reviews`.
+fn attach_reviews(pull_requests: Vec<ParsedPR>, mut reviews: HashMap<u64, Vec<Review>>) -> Vec<ParsedPR> {
+    pull_requests.into_iter()
+        .map(|pr| {
+            let reviews = reviews.remove(&pr.id).into_iter().flatten().filter_map(parse_review).collect();
+            pr.with_reviews(reviews)
+        })
+        .collect()
+}
+
+fn parse_or_skip(pr: PullRequest, repository: &Repository, warnings: &mut Vec<String>) -> Option<ParsedPR> {
+    let number = pr.number;
+    match parse_pr(pr, repository) {
+        Ok(pr) => Some(pr),
+        Err(why) => {
+            eprintln!("Skipping {repository}#{number}: {why}");
+            warnings.push(format!("Skipped {repository}#{number}: {why}"));
+            None
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use crate::github::{source::*, LabelPattern, PRState, Window};
+
+    #[tokio::test]
+    async fn recorded_run_is_replayed() {
+        let fixtures = FixtureSource::new(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures"));
+        let repository = Repository::new("octo", "cat");
+        let date = Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+        let root = std::env::temp_dir().join(format!("xatu-recording-{}", std::process::id()));
+        let recorder = Recorder::new(&root, date);
+        assert_eq!(recorder.run(), "20260310T120000Z");
+        let mut config = Config::default();
+        config.report.timezone = chrono_tz::Asia::Tokyo;
+        config.report.windows = vec![Window::Since(date.date_naive()), Window::SinceRelease];
+        config.labels.breakdown = vec![LabelPattern::Prefix(String::from("type: ")), LabelPattern::Exact(String::from("bug"))];
+
+        let issues: Vec<Issue> = fixtures.load(repository_path(&repository, "issues.json")).unwrap();
+        recorder.save(&repository_path(&repository, "issues.json"), &issues).unwrap();
+        recorder.finish(&Run { config: config.clone(), reports: vec![(repository.clone(), date)] }).unwrap();
+
+        let replay = FixtureSource::new(root.join(recorder.run()));
+        let run = replay.run().unwrap();
+        assert_eq!(run.reports, vec![(repository.clone(), date)]);
+        // Replays report with the configuration of the run rather than the current one
+        assert_eq!(run.config.report.timezone, config.report.timezone);
+        assert_eq!(run.config.report.windows, config.report.windows);
+        assert_eq!(run.config.labels.breakdown, config.labels.breakdown);
+        assert_eq!(run.config.labels.categories, config.labels.categories);
+        let since = Utc.with_ymd_and_hms(2026, 3, 6, 0, 0, 0).unwrap();
+        let replayed = replay.issues(&repository, Some(since), &mut Vec::new()).await.unwrap();
+        assert_eq!(replayed.iter().map(|i| i.id).collect::<Vec<u64>>(), vec![3, 2, 4]);
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn reviews_decide_the_state_of_replayed_pull_requests() {
+        let fixtures = FixtureSource::new(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures"));
+        let repository = Repository::new("octo", "cat");
+        let root = std::env::temp_dir().join(format!("xatu-reviews-{}", std::process::id()));
+        let recorder = Recorder::new(&root, Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap());
+        let pull_requests: Vec<PullRequest> = fixtures.load(repository_path(&repository, "pulls.json")).unwrap();
+        let mut reviews: HashMap<u64, Vec<Review>> = fixtures.load(repository_path(&repository, "reviews.json")).unwrap();
+        // jirachi approved #10, and now #8 too
+        reviews.insert(8, reviews[&10].clone());
+        recorder.save(&repository_path(&repository, "pulls.json"), &pull_requests).unwrap();
+        recorder.save(&repository_path(&repository, "reviews.json"), &reviews).unwrap();
+
+        let replayed = FixtureSource::new(root.join(recorder.run())).pull_requests(&repository, None, None, &mut Vec::new()).await.unwrap();
+        let state = |number: u64| replayed.iter().find(|p| p.id == number).unwrap().state;
+        assert_eq!(state(8), PRState::Approved);
+        assert_eq!(state(10), PRState::Merged);
+        assert_eq!(state(9), PRState::Draft);
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn searches_of_every_repository_are_replayed() {
+        let fixtures = FixtureSource::new(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures"));
+        let searches: HashMap<String, Vec<SearchItem>> = fixtures.load("search.json").unwrap();
+        let root = std::env::temp_dir().join(format!("xatu-searches-{}", std::process::id()));
+        let recorder = Recorder::new(&root, Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap());
+        let config = Arc::new(crate::config::Config::default());
+        let queries = |repository: &Repository| crate::github::GithubData::new(repository.clone(), Arc::clone(&config)).list_queries();
+
+        let (cat, dog) = (Repository::new("octo", "cat"), Repository::new("octo", "dog"));
+        recorder.save_searches(&searches).unwrap();
+        let dog_searches = searches.iter().map(|(query, items)| (query.replace("repo:octo/cat", "repo:octo/dog"), items.clone())).collect();
+        recorder.save_searches(&dog_searches).unwrap();
+
+        let replay = FixtureSource::new(root.join(recorder.run()));
+        for repository in [&cat, &dog] {
+            let lists = replay.lists(&queries(repository), &mut Vec::new()).await.unwrap();
+            assert_eq!(lists.recent_pull_requests.iter().map(|p| p.id).collect::<Vec<u64>>(), vec![8, 9]);
+        }
+        fs::remove_dir_all(root).unwrap();
+    }
+}
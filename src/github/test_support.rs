@@ -0,0 +1,37 @@
+use chrono::{DateTime, Utc};
+use octocrab::models::IssueState;
+
+use crate::github::{PRState, ParsedIssue, ParsedPR};
+
+/// An issue created at `created`, and closed at `closed` if any, to adjust with struct update syntax.
+pub fn issue(created: DateTime<Utc>, closed: Option<DateTime<Utc>>) -> ParsedIssue {
+    ParsedIssue {
+        user: String::from("octocat"),
+        id: 1,
+        url: String::new(),
+        title: String::new(),
+        state: if closed.is_some() { IssueState::Closed } else { IssueState::Open },
+        creation_date: created,
+        updated_date: closed.unwrap_or(created),
+        closed_date: closed,
+        labels: Vec::new(),
+    }
+}
+
+/// A pull request in `state` created at `created`, and closed at `closed` if any, without reviews.
+pub fn pull_request(created: DateTime<Utc>, closed: Option<DateTime<Utc>>, state: PRState) -> ParsedPR {
+    ParsedPR {
+        user: String::from("octocat"),
+        id: 1,
+        url: String::new(),
+        title: String::new(),
+        state,
+        open_state: if closed.is_some() { IssueState::Closed } else { IssueState::Open },
+        creation_date: created,
+        updated_date: closed.unwrap_or(created),
+        closed_date: closed,
+        labels: Vec::new(),
+        requested_reviewers: Vec::new(),
+        reviews: Vec::new(),
+    }
+}
@@ -0,0 +1,195 @@
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Datelike, Days, NaiveDate, TimeDelta, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+/// A reporting window, configured by its name such as "this week" or "last 90 days".
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Window {
+    Today,
+    Yesterday,
+    /// Since Monday.
+    Week,
+    /// Since the first day of the month.
+    Month,
+    /// Since the first day of the year.
+    Year,
+    /// Since the day `n` days before yesterday.
+    LastDays(u16),
+    /// Since the latest release was published, or all time without one.
+    SinceRelease,
+    Since(NaiveDate),
+    All,
+}
+
+impl Window {
+    pub const DEFAULT: [Window; 5] = [Self::Yesterday, Self::LastDays(7), Self::LastDays(30), Self::LastDays(365), Self::All];
+
+    pub fn label(&self) -> String {
+        match self {
+            Self::Today => String::from("today"),
+            Self::Yesterday => String::from("yesterday"),
+            Self::Week => String::from("this week"),
+            Self::Month => String::from("this month"),
+            Self::Year => String::from("this year"),
+            Self::LastDays(days) => format!("the last {days} days"),
+            Self::SinceRelease => String::from("since the latest release"),
+            Self::Since(day) => format!("since {day}"),
+            Self::All => String::from("all time"),
+        }
+    }
+
+    /// Bounds of the window for a report made at `now`, with days starting at midnight in `timezone`.
+    pub fn span(&self, now: DateTime<Utc>, timezone: Tz, release: Option<DateTime<Utc>>) -> Span {
+        let midnight = |day: NaiveDate| {
+            let local = day.and_time(chrono::NaiveTime::MIN);
+            timezone.from_local_datetime(&local).earliest()
+                .unwrap_or_else(|| timezone.from_utc_datetime(&local))
+                .to_utc()
+        };
+        let today = now.with_timezone(&timezone).date_naive();
+        let yesterday = today - Days::new(1);
+        let tomorrow = midnight(today + Days::new(1));
+        let since = |day: NaiveDate| Span { start: Some(midnight(day)), end: tomorrow };
+        match self {
+            Self::Today => since(today),
+            Self::Yesterday => Span { start: Some(midnight(yesterday)), end: midnight(today) },
+            Self::Week => since(today - Days::new(today.weekday().num_days_from_monday() as u64)),
+            Self::Month => since(today.with_day(1).unwrap()),
+            Self::Year => since(today.with_ordinal(1).unwrap()),
+            Self::LastDays(days) => since(yesterday - Days::new(*days as u64)),
+            Self::SinceRelease => Span { start: release, end: tomorrow },
+            Self::Since(day) => since(*day),
+            Self::All => Span { start: None, end: tomorrow },
+        }
+    }
+}
+
+impl fmt::Display for Window {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Today => write!(f, "today"),
+            Self::Yesterday => write!(f, "yesterday"),
+            Self::Week => write!(f, "this week"),
+            Self::Month => write!(f, "this month"),
+            Self::Year => write!(f, "this year"),
+            Self::LastDays(days) => write!(f, "last {days} days"),
+            Self::SinceRelease => write!(f, "since release"),
+            Self::Since(day) => write!(f, "since {day}"),
+            Self::All => write!(f, "all time"),
+        }
+    }
+}
+
+impl FromStr for Window {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let unknown = || format!("unknown window \"{s}\", expected today, yesterday, this week, this month, this year, last <n> days, since release, since <YYYY-MM-DD> or all time");
+        match s {
+            "today" => Ok(Self::Today),
+            "yesterday" => Ok(Self::Yesterday),
+            "this week" => Ok(Self::Week),
+            "this month" => Ok(Self::Month),
+            "this year" => Ok(Self::Year),
+            "since release" => Ok(Self::SinceRelease),
+            "all time" => Ok(Self::All),
+            s => {
+                if let Some(days) = s.strip_prefix("last ").and_then(|s| s.strip_suffix(" days")) {
+                    return days.parse().ok().filter(|days| *days > 0).map(Self::LastDays).ok_or_else(unknown);
+                }
+                s.strip_prefix("since ")
+                    .and_then(|day| NaiveDate::parse_from_str(day, "%Y-%m-%d").ok())
+                    .map(Self::Since)
+                    .ok_or_else(unknown)
+            },
+        }
+    }
+}
+
+impl TryFrom<String> for Window {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Window> for String {
+    fn from(window: Window) -> Self {
+        window.to_string()
+    }
+}
+
+/// The instants counted by a window.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Span {
+    /// First instant counted, `None` for all time.
+    pub start: Option<DateTime<Utc>>,
+    /// First instant no longer counted.
+    pub end: DateTime<Utc>,
+}
+
+impl Span {
+    pub fn contains(&self, date: DateTime<Utc>) -> bool {
+        self.start.is_none_or(|start| date >= start) && date < self.end
+    }
+
+    /// First and last days of the span in `timezone`, `None` for all time.
+    pub fn days(&self, timezone: Tz) -> Option<(NaiveDate, NaiveDate)> {
+        let first = self.start?.with_timezone(&timezone).date_naive();
+        let last = (self.end - TimeDelta::seconds(1)).with_timezone(&timezone).date_naive();
+        Some((first, last))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::github::window::*;
+
+    fn now() -> DateTime<Utc> {
+        // A Tuesday
+        Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap()
+    }
+
+    fn days(window: Window) -> Option<(NaiveDate, NaiveDate)> {
+        window.span(now(), Tz::UTC, None).days(Tz::UTC)
+    }
+
+    fn day(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    #[test]
+    fn windows_are_parsed_from_their_names() {
+        for window in [Window::Today, Window::Week, Window::Month, Window::Year, Window::SinceRelease, Window::Since(day(1, 15))]
+            .into_iter().chain(Window::DEFAULT) {
+            assert_eq!(window.to_string().parse::<Window>(), Ok(window));
+        }
+        assert_eq!("last 90 days".parse::<Window>(), Ok(Window::LastDays(90)));
+        assert!("last 0 days".parse::<Window>().is_err());
+        assert!("since yesterday".parse::<Window>().is_err());
+    }
+
+    #[test]
+    fn calendar_windows_start_on_their_first_day() {
+        assert_eq!(days(Window::Today), Some((day(3, 10), day(3, 10))));
+        assert_eq!(days(Window::Yesterday), Some((day(3, 9), day(3, 9))));
+        assert_eq!(days(Window::Week), Some((day(3, 9), day(3, 10))));
+        assert_eq!(days(Window::Month), Some((day(3, 1), day(3, 10))));
+        assert_eq!(days(Window::Year), Some((day(1, 1), day(3, 10))));
+        assert_eq!(days(Window::LastDays(7)), Some((day(3, 2), day(3, 10))));
+        assert_eq!(days(Window::All), None);
+    }
+
+    #[test]
+    fn release_window_starts_at_the_release() {
+        let release = Utc.with_ymd_and_hms(2026, 3, 4, 10, 0, 0).unwrap();
+        let span = Window::SinceRelease.span(now(), Tz::UTC, Some(release));
+        assert!(!span.contains(release - TimeDelta::seconds(1)));
+        assert!(span.contains(release));
+        assert_eq!(Window::SinceRelease.span(now(), Tz::UTC, None).start, None);
+    }
+}
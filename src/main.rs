@@ -1,28 +1,49 @@
+mod charts;
+mod clock;
+mod commands;
+mod config;
+mod dashboard;
+mod db;
+mod gist;
 #[allow(dead_code)]
 mod github;
 mod utils;
 
-use std::{env, sync::{atomic::{AtomicBool, Ordering}, Arc}, time::Duration};
+use std::{env, path::Path, sync::{atomic::{AtomicBool, Ordering}, Arc}, time::Duration};
+use chrono::{DateTime, NaiveDate};
+use clock::{Clock, FixedClock, SystemClock};
+use config::{Api, Config, DEFAULT_CONFIG_PATH};
+use db::Database;
 use dotenvy::dotenv;
-use github::GithubData;
-use serenity::{all::{ChannelId, Client, Context, EventHandler, GuildId, Message, Ready}, async_trait, Error};
+use github::{Bucket, FixtureSource, GithubData, GithubSource, GraphqlSource, RateLimiter, Recorder, RestSource, Run};
+use serenity::{all::{ChannelId, Client, Command, Context, CreateInteractionResponse, EventHandler, GuildId, Interaction, Ready}, async_trait};
+use tokio::sync::RwLock;
 
 struct Handler {
+    config: Arc<Config>,
+    database: Arc<Database>,
+    reports: Arc<RwLock<Vec<GithubData>>>,
+    clock: Arc<dyn Clock>,
+    gist_id: Option<String>,
     is_loop_running: AtomicBool,
 }
 
 #[async_trait]
 impl EventHandler for Handler {
-    async fn message(&self, ctx: Context, msg: Message) {
-        if msg.content.starts_with("!ping") {
-            if let Err(why) = msg.channel_id.say(&ctx.http, "Pong!").await {
-                eprintln!("Error sending message: {why:?}");
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        if let Interaction::Command(command) = interaction {
+            let response = commands::respond(&command, &self.reports.read().await, &self.database, &self.config);
+            if let Err(why) = command.create_response(&ctx.http, CreateInteractionResponse::Message(response)).await {
+                eprintln!("Error answering /{}: {why:?}", command.data.name);
             }
         }
     }
 
-    async fn ready(&self, _ctx: Context, ready: Ready) {
+    async fn ready(&self, ctx: Context, ready: Ready) {
         println!("{} is connected!", ready.user.name);
+        if let Err(why) = Command::set_global_commands(&ctx.http, commands::register(&self.config)).await {
+            eprintln!("Error registering commands: {why:?}");
+        }
     }
 
     async fn cache_ready(&self, ctx: Context, _guilds: Vec<GuildId>) {
@@ -31,20 +52,110 @@ impl EventHandler for Handler {
 
         if !self.is_loop_running.load(Ordering::Relaxed) {
             let ctx1 = Arc::clone(&ctx);
+            let config = Arc::clone(&self.config);
+            let database = Arc::clone(&self.database);
+            let latest_reports = Arc::clone(&self.reports);
+            let gist_id = self.gist_id.clone();
+            let clock = Arc::clone(&self.clock);
             tokio::spawn(async move {
+                let limiter = Arc::new(RateLimiter::default());
+                // Last successfully fetched report of each repository, without the warnings of later failures
+                let mut fetched: Vec<GithubData> = Vec::new();
                 loop {
-                    println!("Starting loading GH data");
-                    let mut github_data = GithubData::new();
-                    github_data.fetch().await;
-
-                    let output = github_data.render();
-                    println!("Github data loaded");
+                    let recorder = config.recording.enabled.then(|| Recorder::new(&config.recording.dir, clock.now()));
+                    let source: Box<dyn GithubSource> = match (config.github.api, &recorder) {
+                        (Api::Rest, Some(recorder)) => Box::new(RestSource::new(Arc::clone(&limiter)).recording(recorder.clone())),
+                        (Api::Rest, None) => Box::new(RestSource::new(Arc::clone(&limiter))),
+                        (Api::Graphql, _) => Box::new(GraphqlSource::new(Arc::clone(&limiter))),
+                    };
+                    let mut run = Run { config: Config::clone(&config), reports: Vec::new() };
+                    let mut reports = Vec::new();
+                    let mut failures = Vec::new();
+                    let mut refreshed = 0;
+                    for repository in &config.github.repositories {
+                        println!("Starting loading GH data for {repository}");
+                        let mut github_data = GithubData::new(repository.clone(), Arc::clone(&config));
+                        match github_data.fetch(&database, source.as_ref(), clock.as_ref()).await {
+                            Ok(()) => {
+                                println!("Github data loaded");
+                                run.reports.push((repository.clone(), github_data.date()));
+                                if let Err(why) = database.save_snapshot(&github_data.snapshot()) {
+                                    eprintln!("Error saving snapshot of {repository}: {why}");
+                                }
+                                fetched.retain(|r| r.scope() != github_data.scope());
+                                fetched.push(github_data.clone());
+                                reports.push(github_data);
+                                refreshed += 1;
+                            },
+                            Err(why) => {
+                                // Keep showing the last good report rather than nothing
+                                eprintln!("Error loading GH data for {repository}: {why}");
+                                match fetched.iter().find(|r| r.scope() == repository.to_string()) {
+                                    Some(previous) => {
+                                        let mut previous = previous.clone();
+                                        previous.warn(format!("Refresh failed, showing the previous report: {why}"));
+                                        reports.push(previous);
+                                    },
+                                    None => failures.push(format!("{repository} is missing: {why}")),
+                                }
+                            },
+                        }
+                    }
+                    if let Some(recorder) = &recorder {
+                        match recorder.finish(&run) {
+                            Ok(()) => println!("Run recorded, replay it with `xatu replay {}`", recorder.run()),
+                            Err(why) => eprintln!("Error recording the run: {why}"),
+                        }
+                    }
+                    limiter.refresh().await;
+                    for bucket in [Bucket::Core, Bucket::Search, Bucket::Graphql] {
+                        if let Some(budget) = limiter.budget(bucket) {
+                            println!("Rate limit ({bucket}): {budget}");
+                        }
+                    }
+                    let refresh_interval = Duration::from_secs(config.github.refresh_interval);
+                    let interval = limiter.refresh_interval(refresh_interval);
+                    if interval > refresh_interval {
+                        println!("Waiting {} seconds instead of {} until the next refresh, to stay within the rate limit", interval.as_secs(), refresh_interval.as_secs());
+                    }
+                    if reports.is_empty() {
+                        eprintln!("No report could be loaded, retrying in {} seconds", interval.as_secs());
+                        tokio::time::sleep(interval).await;
+                        continue;
+                    }
+                    if config.github.repositories.len() > 1 {
+                        let mut aggregate = GithubData::aggregate(&reports);
+                        for failure in failures {
+                            aggregate.warn(failure);
+                        }
+                        if refreshed == config.github.repositories.len() {
+                            if let Err(why) = database.save_snapshot(&aggregate.snapshot()) {
+                                eprintln!("Error saving snapshot of {}: {why}", aggregate.scope());
+                            }
+                        }
+                        reports.push(aggregate);
+                    }
+                    if reports.iter().any(|r| !r.warnings().is_empty()) {
+                        println!("Degraded run, the report lists what went wrong");
+                    }
 
-                    let channel_id = ChannelId::new(875622508026544148);
-                    if let Err(why) = channel_id.say(&ctx1.http, &output).await {
-                        println!("Error sending message in channel: {:?}", why);
+                    let channel_id = ChannelId::new(config.discord.channel);
+                    let mut messages = dashboard::render(&reports, config.discord.format);
+                    if let (true, Some(report)) = (config.charts.scheduled, reports.last()) {
+                        match charts::render(&database, report, config.charts.days as usize) {
+                            Ok(png) => messages.push(dashboard::ReportMessage::chart(format!("**Trends over the last {} days**", config.charts.days), png)),
+                            Err(why) => eprintln!("Error drawing the chart of {}: {why}", report.scope()),
+                        }
                     }
-                    tokio::time::sleep(Duration::from_secs(400)).await;
+                    match config.discord.dashboard {
+                        true => dashboard::update(&ctx1.http, &database, channel_id, &messages).await,
+                        false => dashboard::post(&ctx1.http, channel_id, &messages).await,
+                    }
+                    if let Some(gist_id) = &gist_id {
+                        gist::upload_daily(&database, gist_id, &reports, clock.now(), config.report.timezone).await;
+                    }
+                    *latest_reports.write().await = reports;
+                    tokio::time::sleep(interval).await;
                 }
             });
             self.is_loop_running.swap(true, Ordering::Relaxed);
@@ -52,9 +163,123 @@ impl EventHandler for Handler {
     }
 }
 
+fn print_history(config: &Config, database: &Database, date: &str) {
+    let Some(end_of_day) = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok().and_then(|d| d.and_hms_opt(23, 59, 59)) else {
+        eprintln!("Invalid date {date}, expected YYYY-MM-DD");
+        std::process::exit(1);
+    };
+    let mut scopes: Vec<String> = config.github.repositories.iter().map(|r| r.to_string()).collect();
+    if scopes.len() > 1 {
+        scopes.push(scopes.join(","));
+    }
+    for scope in scopes {
+        match database.snapshot_at(&scope, end_of_day.and_utc()) {
+            Ok(Some(snapshot)) => println!(
+                "{scope} as of {}: {} open issues ({}{} uncategorised), {} open pull requests ({} ready, {} draft)",
+                snapshot.taken_at, snapshot.open_issues,
+                snapshot.categories.iter().map(|(name, count)| format!("{count} {name}, ")).collect::<String>(), snapshot.uncategorised_issues,
+                snapshot.open_pull_requests, snapshot.ready_pull_requests, snapshot.draft_pull_requests),
+            Ok(None) => println!("{scope}: no snapshot on or before {date}"),
+            Err(why) => eprintln!("Error reading snapshots of {scope}: {why}"),
+        }
+    }
+}
+
+/// Rebuilds the reports of a recorded run from its responses and with its configuration, without GitHub.
+async fn replay(config: &Config, run_id: &str) {
+    let source = FixtureSource::new(Path::new(&config.recording.dir).join(run_id));
+    let run = match source.run() {
+        Ok(run) => run,
+        Err(why) => {
+            eprintln!("Error reading run {run_id}: {why}");
+            std::process::exit(1);
+        }
+    };
+    let config = Arc::new(run.config);
+    let mut reports = Vec::new();
+    for (repository, date) in run.reports {
+        let database = Database::open_in_memory().expect("Failed opening an in-memory database");
+        let mut report = GithubData::new(repository.clone(), Arc::clone(&config));
+        if let Err(why) = report.fetch(&database, &source, &FixedClock(date)).await {
+            eprintln!("Error replaying {repository}: {why}");
+            std::process::exit(1);
+        }
+        reports.push(report);
+    }
+    if reports.len() > 1 {
+        reports.push(GithubData::aggregate(&reports));
+    }
+    for report in reports {
+        println!("{}", report.render_plain());
+    }
+}
+
+/// Rebuilds the reports as of a past instant from the cached issues and pull requests, without GitHub.
+fn print_report(config: &Arc<Config>, database: &Database, clock: &dyn Clock, instant: &str) {
+    let date = match DateTime::parse_from_rfc3339(instant) {
+        Ok(date) => Some(date.to_utc()),
+        Err(_) => NaiveDate::parse_from_str(instant, "%Y-%m-%d").ok().and_then(|d| d.and_hms_opt(23, 59, 59)).map(|d| d.and_utc()),
+    };
+    let Some(date) = date.filter(|d| *d <= clock.now()) else {
+        eprintln!("Invalid instant {instant}, expected a past YYYY-MM-DD or RFC 3339 date");
+        std::process::exit(1);
+    };
+    let mut reports = Vec::new();
+    for repository in &config.github.repositories {
+        let mut report = GithubData::new(repository.clone(), Arc::clone(config));
+        if let Err(why) = report.compute(database, &FixedClock(date)) {
+            eprintln!("Error computing the report of {repository}: {why}");
+            std::process::exit(1);
+        }
+        report.warn(String::from("Backfilled from the cache: the stale and recent lists are not available"));
+        reports.push(report);
+    }
+    if reports.len() > 1 {
+        reports.push(GithubData::aggregate(&reports));
+    }
+    for report in reports {
+        println!("{}", report.render_plain());
+    }
+}
+
 #[tokio::main]
-async fn main() -> Result<(), Error> {
-    dotenv().expect(".env file not found");
+async fn main() {
+    // Replaying and reading the history need no token
+    dotenv().ok();
+
+    let config_path = env::var("XATU_CONFIG").unwrap_or(DEFAULT_CONFIG_PATH.to_string());
+    let config = match Config::load(&config_path) {
+        Ok(config) => Arc::new(config),
+        Err(why) => {
+            eprintln!("Error loading {config_path}: {why}");
+            std::process::exit(1);
+        }
+    };
+
+    let database = match Database::open(&config.database.path) {
+        Ok(database) => Arc::new(database),
+        Err(why) => {
+            eprintln!("Error opening database {}: {why}", config.database.path);
+            std::process::exit(1);
+        }
+    };
+
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.as_slice() {
+        [command, date] if command == "history" => {
+            print_history(&config, &database, date);
+            return;
+        },
+        [command, instant] if command == "report" => {
+            print_report(&config, &database, &SystemClock, instant);
+            return;
+        },
+        [command, run_id] if command == "replay" => {
+            replay(&config, run_id).await;
+            return;
+        },
+        _ => {},
+    }
 
     octocrab::initialise(octocrab::Octocrab::builder()
         .personal_token(env::var("GITHUB_TOKEN").expect("Expected a token in the environment"))
@@ -66,11 +291,17 @@ async fn main() -> Result<(), Error> {
 
     let mut client = Client::builder(&token, intents)
         .event_handler(Handler {
+            config,
+            database,
+            reports: Arc::new(RwLock::new(Vec::new())),
+            clock: Arc::new(SystemClock),
+            gist_id: env::var("GIST_ID").ok().filter(|id| !id.is_empty()),
             is_loop_running: AtomicBool::new(false),
         })
         .await
         .expect("Err creating client");
 
-    client.start().await?;
-    Ok(())
+    if let Err(why) = client.start().await {
+        eprintln!("Client error: {why:?}");
+    }
 }
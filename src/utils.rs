@@ -1,66 +1,185 @@
-use std::time::{SystemTime, UNIX_EPOCH};
-
-pub fn format_timestamp_since(timestamp: u64) -> String {
-    let seconds_since_timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() - timestamp;
-    let unit: &str;
-    let time = if seconds_since_timestamp >= 2*604800/*60*60*24*7*/ {
-        unit = "weeks";
-        seconds_since_timestamp/604800
-    } else if seconds_since_timestamp >= 2*86400/*60*60*24*/ {
-        unit = "days";
-        seconds_since_timestamp/86400
+use chrono::{DateTime, TimeDelta, Utc};
+
+/// How update dates are shown in the lists.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Timestamps {
+    /// "3 days ago", relative to the report date.
+    Relative,
+    /// Discord's timestamp markup, which readers see as a live relative time with the exact date on hover.
+    Discord,
+}
+
+pub fn format_timestamp_since(timestamp: DateTime<Utc>, now: DateTime<Utc>, timestamps: Timestamps) -> String {
+    match timestamps {
+        Timestamps::Relative => format!("Last updated {}", format_relative(timestamp, now)),
+        Timestamps::Discord => format!("Last updated <t:{}:R>", timestamp.timestamp()),
+    }
+}
+
+/// Formats the time between `timestamp` and `now`, such as "1 hour ago" or "in 3 days" for timestamps ahead of
+/// `now` because of clock skew. A unit is only used once two of it have passed, minutes and hours aside.
+pub fn format_relative(timestamp: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    const MINUTE: u64 = 60;
+    const HOUR: u64 = 60*MINUTE;
+    const DAY: u64 = 24*HOUR;
+    const WEEK: u64 = 7*DAY;
+    const MONTH: u64 = 30*DAY;
+    const YEAR: u64 = 365*DAY;
+
+    let seconds = (now - timestamp).num_seconds();
+    let elapsed = seconds.unsigned_abs();
+    let (time, unit) = if elapsed < MINUTE {
+        return String::from("just now");
+    } else if elapsed < HOUR {
+        (elapsed/MINUTE, "minute")
+    } else if elapsed < 2*DAY {
+        (elapsed/HOUR, "hour")
+    } else if elapsed < 2*WEEK {
+        (elapsed/DAY, "day")
+    } else if elapsed < 13*WEEK {
+        (elapsed/WEEK, "week")
+    } else if elapsed < 2*YEAR {
+        (elapsed/MONTH, "month")
     } else {
-        unit = "hours";
-        seconds_since_timestamp/3600
+        (elapsed/YEAR, "year")
     };
-    format!("Last updated {time} {unit} ago")
+    let plural = if time == 1 { "" } else { "s" };
+    match seconds < 0 {
+        true => format!("in {time} {unit}{plural}"),
+        false => format!("{time} {unit}{plural} ago"),
+    }
+}
+
+/// Formats a duration with its two largest units, such as "3d 4h" or "5h 12m".
+pub fn format_duration(duration: TimeDelta) -> String {
+    let minutes = duration.num_minutes().max(0);
+    match (minutes / (24*60), minutes / 60 % 24, minutes % 60) {
+        (0, 0, minutes) => format!("{minutes}m"),
+        (0, hours, minutes) => format!("{hours}h {minutes}m"),
+        (days, hours, _) => format!("{days}d {hours}h"),
+    }
 }
 
+pub fn encode_query(query: &str) -> String {
+    let mut encoded = String::with_capacity(query.len());
+    for byte in query.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => encoded.push(byte as char),
+            b' ' => encoded.push('+'),
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
 
 #[cfg(test)]
 mod tests {
-    use std::time::{SystemTime, UNIX_EPOCH};
+    use chrono::{Duration, TimeZone};
 
     use crate::utils::*;
 
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn query_encoding() {
+        assert_eq!(encode_query("\"status: confirmed\""), "%22status%3A+confirmed%22");
+        assert_eq!(encode_query("feature-request"), "feature-request");
+    }
+
     #[test]
     fn weeks_formatting() {
         for i in 2..10 {
-            let result = format_timestamp_since(SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() - i*604800);
+            let result = format_timestamp_since(now() - Duration::weeks(i), now(), Timestamps::Relative);
             assert_eq!(result, format!("Last updated {i} weeks ago"));
         }
     }
     #[test]
     fn weeks_formatting_1() {
-        let result = format_timestamp_since(SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() - 604800);
+        let result = format_timestamp_since(now() - Duration::weeks(1), now(), Timestamps::Relative);
         assert_eq!(result, "Last updated 7 days ago");
     }
     
     #[test]
     fn days_formatting() {
         for i in 2..10 {
-            let result = format_timestamp_since(SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() - i*86400);
+            let result = format_timestamp_since(now() - Duration::days(i), now(), Timestamps::Relative);
             assert_eq!(result, format!("Last updated {i} days ago"));
         }
     }
     
     #[test]
     fn days_formatting_1() {
-        let result = format_timestamp_since(SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() - 86400);
+        let result = format_timestamp_since(now() - Duration::days(1), now(), Timestamps::Relative);
         assert_eq!(result, "Last updated 24 hours ago");
     }
     
     #[test]
     fn hours_formatting() {
         for i in 2..43 {
-            let result = format_timestamp_since(SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() - i*3600);
+            let result = format_timestamp_since(now() - Duration::hours(i), now(), Timestamps::Relative);
             assert_eq!(result, format!("Last updated {i} hours ago"));
         }
     }
     
     #[test]
     fn hours_formatting_1() {
-        let result = format_timestamp_since(SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() - 3600);
-        assert_eq!(result, "Last updated 1 hours ago");
+        let result = format_timestamp_since(now() - Duration::hours(1), now(), Timestamps::Relative);
+        assert_eq!(result, "Last updated 1 hour ago");
+    }
+
+    #[test]
+    fn durations_keep_two_units() {
+        assert_eq!(format_duration(TimeDelta::seconds(59)), "0m");
+        assert_eq!(format_duration(TimeDelta::minutes(59)), "59m");
+        assert_eq!(format_duration(TimeDelta::minutes(61)), "1h 1m");
+        assert_eq!(format_duration(TimeDelta::hours(23) + TimeDelta::minutes(59)), "23h 59m");
+        assert_eq!(format_duration(TimeDelta::days(6) + TimeDelta::hours(2) + TimeDelta::minutes(30)), "6d 2h");
+    }
+
+    #[test]
+    fn recent_times_are_just_now() {
+        for seconds in [0, 1, 59, -1, -59] {
+            assert_eq!(format_relative(now() - Duration::seconds(seconds), now()), "just now");
+        }
+    }
+
+    #[test]
+    fn minutes_formatting() {
+        assert_eq!(format_relative(now() - Duration::minutes(1), now()), "1 minute ago");
+        for i in 2..60 {
+            assert_eq!(format_relative(now() - Duration::minutes(i), now()), format!("{i} minutes ago"));
+        }
+        assert_eq!(format_relative(now() - Duration::seconds(119), now()), "1 minute ago");
+    }
+
+    #[test]
+    fn months_formatting() {
+        assert_eq!(format_relative(now() - Duration::weeks(12) - Duration::days(6), now()), "12 weeks ago");
+        for i in 3..25 {
+            assert_eq!(format_relative(now() - Duration::days(i*30 + 6), now()), format!("{i} months ago"));
+        }
+    }
+
+    #[test]
+    fn years_formatting() {
+        for i in 2..20 {
+            assert_eq!(format_relative(now() - Duration::days(i*365), now()), format!("{i} years ago"));
+        }
+    }
+
+    #[test]
+    fn future_times_are_ahead() {
+        assert_eq!(format_relative(now() + Duration::minutes(1), now()), "in 1 minute");
+        assert_eq!(format_relative(now() + Duration::hours(5), now()), "in 5 hours");
+        assert_eq!(format_relative(now() + Duration::days(3), now()), "in 3 days");
+        assert_eq!(format_timestamp_since(now() + Duration::seconds(30), now(), Timestamps::Relative), "Last updated just now");
+    }
+
+    #[test]
+    fn discord_formatting() {
+        let result = format_timestamp_since(now() - Duration::hours(3), now(), Timestamps::Discord);
+        assert_eq!(result, format!("Last updated <t:{}:R>", now().timestamp() - 3*3600));
     }
 }